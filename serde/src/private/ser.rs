@@ -8,7 +8,8 @@
 
 use lib::*;
 
-use ser::{self, Serialize, Serializer, SerializeMap, SerializeStruct, Impossible};
+use ser::{self, Serialize, Serializer, SerializeMap, SerializeSeq, SerializeStruct,
+          SerializeTuple, SerializeTupleStruct, Impossible};
 
 #[cfg(any(feature = "std", feature = "alloc"))]
 use self::content::{SerializeTupleVariantAsMapValue, SerializeStructVariantAsMapValue};
@@ -64,7 +65,8 @@ enum Unsupported {
     Sequence,
     Tuple,
     TupleStruct,
-    #[cfg(not(any(feature = "std", feature = "alloc")))]
+    Map,
+    Struct,
     Enum,
 }
 
@@ -83,7 +85,8 @@ impl Display for Unsupported {
             Unsupported::Sequence => formatter.write_str("a sequence"),
             Unsupported::Tuple => formatter.write_str("a tuple"),
             Unsupported::TupleStruct => formatter.write_str("a tuple struct"),
-            #[cfg(not(any(feature = "std", feature = "alloc")))]
+            Unsupported::Map => formatter.write_str("a map"),
+            Unsupported::Struct => formatter.write_str("a struct"),
             Unsupported::Enum => formatter.write_str("an enum"),
         }
     }
@@ -127,6 +130,10 @@ where
     #[cfg(any(feature = "std", feature = "alloc"))]
     type SerializeStructVariant = SerializeStructVariantAsMapValue<S::SerializeMap>;
 
+    fn is_human_readable(&self) -> bool {
+        self.delegate.is_human_readable()
+    }
+
     fn serialize_bool(self, _: bool) -> Result<Self::Ok, Self::Error> {
         Err(self.bad_type(Unsupported::Boolean))
     }
@@ -336,6 +343,695 @@ where
     }
 }
 
+/// Serializes the entries of a struct or map into an enclosing map. Used for
+/// `#[serde(flatten)]` fields. The flag is whether the enclosing serializer
+/// is human readable. Not public API.
+pub struct FlatMapSerializer<'a, M: 'a>(pub &'a mut M, pub bool);
+
+impl<'a, M> FlatMapSerializer<'a, M>
+where
+    M: SerializeMap + 'a,
+{
+    fn bad_type(what: Unsupported) -> M::Error {
+        ser::Error::custom(format_args!("can only flatten structs and maps (got {})", what))
+    }
+}
+
+impl<'a, M> Serializer for FlatMapSerializer<'a, M>
+where
+    M: SerializeMap + 'a,
+{
+    type Ok = ();
+    type Error = M::Error;
+
+    type SerializeSeq = Impossible<Self::Ok, M::Error>;
+    type SerializeTuple = Impossible<Self::Ok, M::Error>;
+    type SerializeTupleStruct = Impossible<Self::Ok, M::Error>;
+    type SerializeMap = FlatMapSerializeMap<'a, M>;
+    type SerializeStruct = FlatMapSerializeStruct<'a, M>;
+    type SerializeTupleVariant = Impossible<Self::Ok, M::Error>;
+    type SerializeStructVariant = Impossible<Self::Ok, M::Error>;
+
+    fn is_human_readable(&self) -> bool {
+        self.1
+    }
+
+    fn serialize_bool(self, _: bool) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::Boolean))
+    }
+
+    fn serialize_i8(self, _: i8) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::Integer))
+    }
+
+    fn serialize_i16(self, _: i16) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::Integer))
+    }
+
+    fn serialize_i32(self, _: i32) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::Integer))
+    }
+
+    fn serialize_i64(self, _: i64) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::Integer))
+    }
+
+    fn serialize_u8(self, _: u8) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::Integer))
+    }
+
+    fn serialize_u16(self, _: u16) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::Integer))
+    }
+
+    fn serialize_u32(self, _: u32) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::Integer))
+    }
+
+    fn serialize_u64(self, _: u64) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::Integer))
+    }
+
+    fn serialize_f32(self, _: f32) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::Float))
+    }
+
+    fn serialize_f64(self, _: f64) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::Float))
+    }
+
+    fn serialize_char(self, _: char) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::Char))
+    }
+
+    fn serialize_str(self, _: &str) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::String))
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::ByteArray))
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::Optional))
+    }
+
+    fn serialize_some<T: ?Sized>(self, _: &T) -> Result<Self::Ok, Self::Error>
+    where
+        T: Serialize,
+    {
+        Err(Self::bad_type(Unsupported::Optional))
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::Unit))
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::UnitStruct))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::Enum))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: Serialize,
+    {
+        Err(Self::bad_type(Unsupported::Enum))
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Err(Self::bad_type(Unsupported::Sequence))
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        Err(Self::bad_type(Unsupported::Tuple))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Err(Self::bad_type(Unsupported::TupleStruct))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Err(Self::bad_type(Unsupported::Enum))
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Ok(FlatMapSerializeMap(self.0))
+    }
+
+    fn serialize_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        Ok(FlatMapSerializeStruct(self.0))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Err(Self::bad_type(Unsupported::Enum))
+    }
+
+    #[cfg(not(any(feature = "std", feature = "alloc")))]
+    fn collect_str<T: ?Sized>(self, _: &T) -> Result<Self::Ok, Self::Error>
+    where
+        T: Display,
+    {
+        Err(Self::bad_type(Unsupported::String))
+    }
+}
+
+/// Not public API.
+pub struct FlatMapSerializeMap<'a, M: 'a>(&'a mut M);
+
+impl<'a, M> SerializeMap for FlatMapSerializeMap<'a, M>
+where
+    M: SerializeMap + 'a,
+{
+    type Ok = ();
+    type Error = M::Error;
+
+    fn serialize_key<T: ?Sized>(&mut self, key: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize,
+    {
+        self.0.serialize_key(key)
+    }
+
+    fn serialize_value<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize,
+    {
+        self.0.serialize_value(value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Not public API.
+pub struct FlatMapSerializeStruct<'a, M: 'a>(&'a mut M);
+
+impl<'a, M> SerializeStruct for FlatMapSerializeStruct<'a, M>
+where
+    M: SerializeMap + 'a,
+{
+    type Ok = ();
+    type Error = M::Error;
+
+    fn serialize_field<T: ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error>
+    where
+        T: Serialize,
+    {
+        self.0.serialize_entry(key, value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Serializes the elements of a tuple or sequence into an enclosing sequence.
+/// Used for `#[serde(flatten)]` fields of tuple structs. The flag is whether
+/// the enclosing serializer is human readable. Not public API.
+pub struct FlatSeqSerializer<'a, S: 'a>(pub &'a mut S, pub bool);
+
+impl<'a, S> FlatSeqSerializer<'a, S>
+where
+    S: SerializeSeq + 'a,
+{
+    fn bad_type(what: Unsupported) -> S::Error {
+        ser::Error::custom(format_args!("can only flatten tuples and sequences (got {})", what))
+    }
+}
+
+impl<'a, S> Serializer for FlatSeqSerializer<'a, S>
+where
+    S: SerializeSeq + 'a,
+{
+    type Ok = ();
+    type Error = S::Error;
+
+    type SerializeSeq = FlatSeqSerializeSeq<'a, S>;
+    type SerializeTuple = FlatSeqSerializeSeq<'a, S>;
+    type SerializeTupleStruct = FlatSeqSerializeSeq<'a, S>;
+    type SerializeMap = Impossible<Self::Ok, S::Error>;
+    type SerializeStruct = Impossible<Self::Ok, S::Error>;
+    type SerializeTupleVariant = Impossible<Self::Ok, S::Error>;
+    type SerializeStructVariant = Impossible<Self::Ok, S::Error>;
+
+    fn is_human_readable(&self) -> bool {
+        self.1
+    }
+
+    fn serialize_bool(self, _: bool) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::Boolean))
+    }
+
+    fn serialize_i8(self, _: i8) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::Integer))
+    }
+
+    fn serialize_i16(self, _: i16) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::Integer))
+    }
+
+    fn serialize_i32(self, _: i32) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::Integer))
+    }
+
+    fn serialize_i64(self, _: i64) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::Integer))
+    }
+
+    fn serialize_u8(self, _: u8) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::Integer))
+    }
+
+    fn serialize_u16(self, _: u16) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::Integer))
+    }
+
+    fn serialize_u32(self, _: u32) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::Integer))
+    }
+
+    fn serialize_u64(self, _: u64) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::Integer))
+    }
+
+    fn serialize_f32(self, _: f32) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::Float))
+    }
+
+    fn serialize_f64(self, _: f64) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::Float))
+    }
+
+    fn serialize_char(self, _: char) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::Char))
+    }
+
+    fn serialize_str(self, _: &str) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::String))
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::ByteArray))
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::Optional))
+    }
+
+    fn serialize_some<T: ?Sized>(self, _: &T) -> Result<Self::Ok, Self::Error>
+    where
+        T: Serialize,
+    {
+        Err(Self::bad_type(Unsupported::Optional))
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::Unit))
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::UnitStruct))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        Err(Self::bad_type(Unsupported::Enum))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: Serialize,
+    {
+        Err(Self::bad_type(Unsupported::Enum))
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Ok(FlatSeqSerializeSeq(self.0))
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        Ok(FlatSeqSerializeSeq(self.0))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Ok(FlatSeqSerializeSeq(self.0))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Err(Self::bad_type(Unsupported::Enum))
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Err(Self::bad_type(Unsupported::Map))
+    }
+
+    fn serialize_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        Err(Self::bad_type(Unsupported::Struct))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Err(Self::bad_type(Unsupported::Enum))
+    }
+
+    #[cfg(not(any(feature = "std", feature = "alloc")))]
+    fn collect_str<T: ?Sized>(self, _: &T) -> Result<Self::Ok, Self::Error>
+    where
+        T: Display,
+    {
+        Err(Self::bad_type(Unsupported::String))
+    }
+}
+
+/// Not public API.
+pub struct FlatSeqSerializeSeq<'a, S: 'a>(&'a mut S);
+
+impl<'a, S> SerializeSeq for FlatSeqSerializeSeq<'a, S>
+where
+    S: SerializeSeq + 'a,
+{
+    type Ok = ();
+    type Error = S::Error;
+
+    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize,
+    {
+        self.0.serialize_element(value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<'a, S> SerializeTuple for FlatSeqSerializeSeq<'a, S>
+where
+    S: SerializeSeq + 'a,
+{
+    type Ok = ();
+    type Error = S::Error;
+
+    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize,
+    {
+        self.0.serialize_element(value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<'a, S> SerializeTupleStruct for FlatSeqSerializeSeq<'a, S>
+where
+    S: SerializeSeq + 'a,
+{
+    type Ok = ();
+    type Error = S::Error;
+
+    fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize,
+    {
+        self.0.serialize_element(value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Serializes a float as a string rounded to a fixed number of decimals, for
+/// fields with `#[serde(float_precision = N)]`. Not public API.
+pub struct FloatPrecision<T>(pub T, pub usize);
+
+impl<T> Serialize for FloatPrecision<T>
+where
+    T: Display,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(&format_args!("{:.*}", self.1, self.0))
+    }
+}
+
+/// What to do with NaN and infinities in fields of a container with
+/// `#[serde(non_finite = "...")]`. Not public API.
+#[derive(Copy, Clone)]
+pub enum NonFiniteMode {
+    Null,
+    Error,
+    String,
+}
+
+/// Serializes an `f32` or `f64` as usual if it is finite, and according to the
+/// mode otherwise. Not public API.
+pub struct NonFinite<T>(pub T, pub NonFiniteMode);
+
+/// Implemented for `f32` and `f64`. Written with comparisons because the
+/// float methods are not available in `core` on every supported compiler.
+/// Not public API.
+pub trait Float: Serialize + Copy {
+    fn is_nan(self) -> bool;
+    fn is_finite(self) -> bool;
+    fn is_negative(self) -> bool;
+}
+
+macro_rules! impl_float {
+    ($ty:ident) => {
+        impl Float for $ty {
+            #[cfg_attr(feature = "cargo-clippy", allow(eq_op))]
+            fn is_nan(self) -> bool {
+                self != self
+            }
+
+            fn is_finite(self) -> bool {
+                // Infinities and NaN give NaN when subtracted from themselves.
+                self - self == 0.0
+            }
+
+            fn is_negative(self) -> bool {
+                self < 0.0
+            }
+        }
+    }
+}
+
+impl_float!(f32);
+impl_float!(f64);
+
+impl<T> Serialize for NonFinite<T>
+where
+    T: Float,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if self.0.is_finite() {
+            return self.0.serialize(serializer);
+        }
+
+        match self.1 {
+            NonFiniteMode::Null => serializer.serialize_none(),
+            NonFiniteMode::Error => Err(ser::Error::custom("cannot serialize a non-finite float")),
+            NonFiniteMode::String => {
+                serializer.serialize_str(
+                    if self.0.is_nan() {
+                        "NaN"
+                    } else if self.0.is_negative() {
+                        "-Infinity"
+                    } else {
+                        "Infinity"
+                    },
+                )
+            }
+        }
+    }
+}
+
+/// Serializes a value as the string produced by its `Display` impl, for
+/// fields with `#[serde(int128_as_string)]`. Not public API.
+pub struct DisplayAsString<'a, T: 'a>(pub &'a T);
+
+impl<'a, T> Serialize for DisplayAsString<'a, T>
+where
+    T: Display,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self.0)
+    }
+}
+
+/// Serializes a `char` as a string of one character, for fields with
+/// `#[serde(char_as_str)]`. Not public API.
+pub struct CharAsStr(pub char);
+
+impl Serialize for CharAsStr {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut buf = [0; 4];
+        serializer.serialize_str(self.0.encode_utf8(&mut buf))
+    }
+}
+
+/// Name of the newtype struct that fields with `#[serde(raw)]` are passed to
+/// the serializer as. A serializer that supports raw output should emit the
+/// string inside such a newtype verbatim instead of as a string.
+pub const RAW_VALUE_TOKEN: &'static str = "$serde::private::RawValue";
+
+/// Serializes already serialized content as a newtype struct named
+/// `RAW_VALUE_TOKEN`, for fields with `#[serde(raw)]`. Not public API.
+pub struct RawValue<'a, T: ?Sized + 'a>(pub &'a T);
+
+impl<'a, T> Serialize for RawValue<'a, T>
+where
+    T: ?Sized + AsRef<str>,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_newtype_struct(RAW_VALUE_TOKEN, self.0.as_ref())
+    }
+}
+
+/// Serializes a byte buffer with `serialize_bytes`, for fields with
+/// `#[serde(serialize_bytes)]`. Not public API.
+pub struct Bytes<'a, T: ?Sized + 'a>(pub &'a T);
+
+impl<'a, T> Serialize for Bytes<'a, T>
+where
+    T: ?Sized + AsRef<[u8]>,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(self.0.as_ref())
+    }
+}
+
+/// Serializes the content of an `Option`, or the sentinel if it is `None`,
+/// for fields with `#[serde(null_sentinel = "...")]`. Not public API.
+pub struct OrSentinel<'a, T: 'a, U>(pub &'a Option<T>, pub U);
+
+impl<'a, T, U> Serialize for OrSentinel<'a, T, U>
+where
+    T: Serialize,
+    U: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match *self.0 {
+            Some(ref value) => value.serialize(serializer),
+            None => self.1.serialize(serializer),
+        }
+    }
+}
+
 /// Used only by Serde doc tests. Not public API.
 #[doc(hidden)]
 #[derive(Debug)]
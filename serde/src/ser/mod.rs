@@ -1292,6 +1292,23 @@ pub trait Serializer: Sized {
         serializer.end()
     }
 
+    /// Determine whether `Serialize` implementations should serialize in
+    /// human-readable form.
+    ///
+    /// Some types have a human-readable form that may be somewhat expensive to
+    /// construct, as well as a binary form that is compact and efficient.
+    /// Generally text-based formats like JSON and YAML will prefer to use the
+    /// human-readable one and binary formats like Bincode will prefer the
+    /// compact one.
+    ///
+    /// The default implementation of this method returns `true`. Data formats
+    /// may override this to `false` to request a compact form for types that
+    /// support one.
+    #[inline]
+    fn is_human_readable(&self) -> bool {
+        true
+    }
+
     /// Serialize a string produced by an implementation of `Display`.
     ///
     /// The default implementation builds a heap-allocated [`String`] and
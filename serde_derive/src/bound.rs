@@ -116,6 +116,31 @@ where
             }
             visit::walk_path(self, path);
         }
+
+        // Type parameters in the arguments of a type macro like `vec_of!(T)`
+        // cannot be located without expanding the macro, so any matching
+        // identifier is treated as a use.
+        fn visit_mac(&mut self, mac: &syn::Mac) {
+            self.visit_tts(&mac.tts);
+            visit::walk_mac(self, mac);
+        }
+    }
+    impl FindTyParams {
+        fn visit_tts(&mut self, tts: &[syn::TokenTree]) {
+            for tt in tts {
+                match *tt {
+                    syn::TokenTree::Token(syn::Token::Ident(ref id)) => {
+                        if self.all_ty_params.contains(id) {
+                            self.relevant_ty_params.insert(id.clone());
+                        }
+                    }
+                    syn::TokenTree::Delimited(ref delimited) => {
+                        self.visit_tts(&delimited.tts);
+                    }
+                    syn::TokenTree::Token(_) => {}
+                }
+            }
+        }
     }
 
     let all_ty_params: HashSet<_> = generics
@@ -19,6 +19,7 @@ use std::collections::BTreeSet;
 pub fn expand_derive_deserialize(input: &syn::DeriveInput) -> Result<Tokens, String> {
     let ctxt = internals::Ctxt::new();
     let cont = Container::from_ast(&ctxt, input);
+    precondition(&ctxt, &cont);
     try!(ctxt.check());
 
     let ident = &cont.ident;
@@ -60,6 +61,64 @@ pub fn expand_derive_deserialize(input: &syn::DeriveInput) -> Result<Tokens, Str
     Ok(generated)
 }
 
+fn precondition(cx: &internals::Ctxt, cont: &Container) {
+    if cont.attrs.as_flags() {
+        cx.error("#[serde(as_flags)] structs cannot be deserialized");
+    }
+    if cont.body.all_fields().any(|field| field.attrs.flatten()) {
+        cx.error("#[serde(flatten)] fields cannot be deserialized");
+    }
+    if cont.attrs.tuple_names().is_some() {
+        cx.error("#[serde(tuple_names)] structs cannot be deserialized");
+    }
+    if cont.attrs.external_as_map() {
+        cx.error("#[serde(external_as_map)] enums cannot be deserialized");
+    }
+    if cont.attrs.scalar_field().is_some() {
+        cx.error("#[serde(scalar_field)] structs cannot be deserialized");
+    }
+    if cont.attrs.key_transform().is_some() {
+        cx.error("#[serde(key_transform)] structs cannot be deserialized");
+    }
+    if cont.attrs.unit_as_null() {
+        cx.error("#[serde(unit_as_null)] structs cannot be deserialized");
+    }
+    if let Body::Enum(ref variants) = cont.body {
+        if variants.iter().any(|variant| variant.attrs.unit_as_null()) {
+            cx.error("enums with #[serde(unit_as_null)] on a variant cannot be deserialized");
+        }
+    }
+    if cont.attrs.as_empty_map() {
+        cx.error("#[serde(as_empty_map)] structs cannot be deserialized");
+    }
+    if cont.attrs.struct_tag().is_some() {
+        cx.error("structs with #[serde(tag = \"...\")] cannot be deserialized");
+    }
+    if let Body::Enum(ref variants) = cont.body {
+        if variants.iter().any(|variant| variant.attrs.tag().is_some()) {
+            cx.error("enums with #[serde(tag = \"...\")] on a variant cannot be deserialized");
+        }
+    }
+    if cont.body.all_fields().any(|field| field.attrs.float_precision().is_some()) {
+        cx.error("#[serde(float_precision)] fields cannot be deserialized");
+    }
+    if cont.body.all_fields().any(|field| field.attrs.int128_as_string()) {
+        cx.error("#[serde(int128_as_string)] fields cannot be deserialized");
+    }
+    if cont.body.all_fields().any(|field| field.attrs.null_sentinel().is_some()) {
+        cx.error("#[serde(null_sentinel)] fields cannot be deserialized");
+    }
+    if cont.body.all_fields().any(|field| field.attrs.serialize_bytes()) {
+        cx.error("#[serde(serialize_bytes)] fields cannot be deserialized");
+    }
+    if cont.body.all_fields().any(|field| field.attrs.serialize_name_const().is_some()) {
+        cx.error("#[serde(rename_const)] fields cannot be deserialized");
+    }
+    if cont.body.all_fields().any(|field| field.attrs.raw()) {
+        cx.error("#[serde(raw)] fields cannot be deserialized");
+    }
+}
+
 struct Parameters {
     /// Name of the type the `derive` is on.
     local: syn::Ident,
@@ -183,6 +242,13 @@ fn borrowed_lifetimes(cont: &Container) -> BTreeSet<syn::Lifetime> {
 fn deserialize_body(cont: &Container, params: &Parameters) -> Fragment {
     if let Some(from_type) = cont.attrs.from_type() {
         deserialize_from(from_type)
+    } else if cont.attrs.transparent() {
+        match cont.body {
+            Body::Enum(ref variants) => {
+                deserialize_untagged_variant(params, &variants[0], &cont.attrs, quote!(__deserializer))
+            }
+            Body::Struct(_, ref fields) => deserialize_transparent(params, &fields[0]),
+        }
     } else if let attr::Identifier::No = cont.attrs.identifier() {
         match cont.body {
             Body::Enum(ref variants) => deserialize_enum(params, variants, &cont.attrs),
@@ -219,6 +285,51 @@ fn deserialize_from(from_type: &syn::Ty) -> Fragment {
     }
 }
 
+fn deserialize_transparent(params: &Parameters, field: &Field) -> Fragment {
+    // If there are getters (implying private fields), construct the local type
+    // and use an `Into` conversion to get the remote type.
+    let construct = if params.has_getter {
+        let local = &params.local;
+        quote!(#local)
+    } else {
+        let this = &params.this;
+        quote!(#this)
+    };
+    let mut result = match field.ident {
+        Some(ref ident) => quote!(#construct { #ident: __transparent }),
+        None => quote!(#construct(__transparent)),
+    };
+    if params.has_getter {
+        let this = &params.this;
+        result = quote! {
+            _serde::export::Into::<#this>::into(#result)
+        };
+    }
+
+    match field.attrs.deserialize_with() {
+        None => {
+            let field_ty = &field.ty;
+            quote_expr! {
+                _serde::export::Result::map(
+                    <#field_ty as _serde::Deserialize>::deserialize(__deserializer),
+                    |__transparent| #result)
+            }
+        }
+        Some(path) => {
+            let (wrapper, wrapper_ty) = wrap_deserialize_with(params, field.ty, path);
+            quote_block! {
+                #wrapper
+                _serde::export::Result::map(
+                    <#wrapper_ty as _serde::Deserialize>::deserialize(__deserializer),
+                    |__wrapper| {
+                        let __transparent = __wrapper.value;
+                        #result
+                    })
+            }
+        }
+    }
+}
+
 fn deserialize_unit_struct(params: &Parameters, cattrs: &attr::Container) -> Fragment {
     let this = &params.this;
     let type_name = cattrs.name().deserialize_name();
@@ -454,12 +565,13 @@ fn deserialize_newtype_struct(type_path: &Tokens, params: &Parameters, field: &F
 }
 
 fn deserialize_struct(
-    variant_ident: Option<&syn::Ident>,
+    variant: Option<&Variant>,
     params: &Parameters,
     fields: &[Field],
     cattrs: &attr::Container,
     deserializer: Option<Tokens>,
 ) -> Fragment {
+    let variant_ident = variant.map(|variant| &variant.ident);
     let is_enum = variant_ident.is_some();
     let is_untagged = deserializer.is_some();
 
@@ -503,6 +615,14 @@ fn deserialize_struct(
         quote! {
             _serde::Deserializer::deserialize_any(#deserializer, #visitor_expr)
         }
+    } else if variant.map_or(false, |variant| variant.attrs.as_seq()) {
+        let len = fields
+            .iter()
+            .filter(|field| !field.attrs.skip_deserializing())
+            .count();
+        quote! {
+            _serde::de::VariantAccess::tuple_variant(__variant, #len, #visitor_expr)
+        }
     } else if is_enum {
         quote! {
             _serde::de::VariantAccess::struct_variant(__variant, FIELDS, #visitor_expr)
@@ -612,7 +732,9 @@ fn deserialize_externally_tagged_enum(
         }
     };
 
-    let variant_visitor = Stmts(deserialize_generated_identifier(variant_names_idents, cattrs, true),);
+    let other_idx = other_variant_index(variants);
+
+    let variant_visitor = Stmts(deserialize_generated_identifier(variant_names_idents, cattrs, true, other_idx),);
 
     // Match arms to extract a variant from a string
     let variant_arms = variants
@@ -705,7 +827,9 @@ fn deserialize_internally_tagged_enum(
         }
     };
 
-    let variant_visitor = Stmts(deserialize_generated_identifier(variant_names_idents, cattrs, true),);
+    let other_idx = other_variant_index(variants);
+
+    let variant_visitor = Stmts(deserialize_generated_identifier(variant_names_idents, cattrs, true, other_idx),);
 
     // Match arms to extract a variant from a string
     let variant_arms = variants.iter()
@@ -765,7 +889,9 @@ fn deserialize_adjacently_tagged_enum(
         }
     };
 
-    let variant_visitor = Stmts(deserialize_generated_identifier(variant_names_idents, cattrs, true),);
+    let other_idx = other_variant_index(variants);
+
+    let variant_visitor = Stmts(deserialize_generated_identifier(variant_names_idents, cattrs, true, other_idx),);
 
     let ref variant_arms: Vec<_> = variants
         .iter()
@@ -812,7 +938,7 @@ fn deserialize_adjacently_tagged_enum(
     };
 
     fn is_unit(variant: &Variant) -> bool {
-        match variant.style {
+        match variant.represented_style() {
             Style::Unit => true,
             Style::Struct | Style::Tuple | Style::Newtype => false,
         }
@@ -838,9 +964,9 @@ fn deserialize_adjacently_tagged_enum(
             .map(
                 |(i, variant)| {
                     let variant_index = field_i(i);
-                    let variant_ident = &variant.ident;
+                    let unit_value = Expr(unit_variant_value(params, variant, cattrs));
                     quote! {
-                        __Field::#variant_index => _serde::export::Ok(#this::#variant_ident),
+                        __Field::#variant_index => _serde::export::Ok(#unit_value),
                     }
                 },
             );
@@ -1086,12 +1212,12 @@ fn deserialize_externally_tagged_variant(
 ) -> Fragment {
     let variant_ident = &variant.ident;
 
-    match variant.style {
+    match variant.represented_style() {
         Style::Unit => {
-            let this = &params.this;
+            let unit_value = Expr(unit_variant_value(params, variant, cattrs));
             quote_block! {
                 try!(_serde::de::VariantAccess::unit_variant(__variant));
-                _serde::export::Ok(#this::#variant_ident)
+                _serde::export::Ok(#unit_value)
             }
         }
         Style::Newtype => {
@@ -1101,7 +1227,7 @@ fn deserialize_externally_tagged_variant(
             deserialize_tuple(Some(variant_ident), params, &variant.fields, cattrs, None)
         }
         Style::Struct => {
-            deserialize_struct(Some(variant_ident), params, &variant.fields, cattrs, None)
+            deserialize_struct(Some(variant), params, &variant.fields, cattrs, None)
         }
     }
 }
@@ -1112,16 +1238,14 @@ fn deserialize_internally_tagged_variant(
     cattrs: &attr::Container,
     deserializer: Tokens,
 ) -> Fragment {
-    let variant_ident = &variant.ident;
-
-    match variant.style {
+    match variant.represented_style() {
         Style::Unit => {
-            let this = &params.this;
+            let unit_value = Expr(unit_variant_value(params, variant, cattrs));
             let type_name = params.type_name();
             let variant_name = variant.ident.as_ref();
             quote_block! {
                 try!(_serde::Deserializer::deserialize_any(#deserializer, _serde::private::de::InternallyTaggedUnitVisitor::new(#type_name, #variant_name)));
-                _serde::export::Ok(#this::#variant_ident)
+                _serde::export::Ok(#unit_value)
             }
         }
         Style::Newtype | Style::Struct => {
@@ -1139,9 +1263,9 @@ fn deserialize_untagged_variant(
 ) -> Fragment {
     let variant_ident = &variant.ident;
 
-    match variant.style {
+    match variant.represented_style() {
         Style::Unit => {
-            let this = &params.this;
+            let unit_value = Expr(unit_variant_value(params, variant, cattrs));
             let type_name = params.type_name();
             let variant_name = variant.ident.as_ref();
             quote_expr! {
@@ -1150,7 +1274,7 @@ fn deserialize_untagged_variant(
                         #deserializer,
                         _serde::private::de::UntaggedUnitVisitor::new(#type_name, #variant_name)
                     ),
-                    |()| #this::#variant_ident)
+                    |()| #unit_value)
             }
         }
         Style::Newtype => {
@@ -1172,7 +1296,7 @@ fn deserialize_untagged_variant(
         }
         Style::Struct => {
             deserialize_struct(
-                Some(variant_ident),
+                Some(variant),
                 params,
                 &variant.fields,
                 cattrs,
@@ -1182,6 +1306,29 @@ fn deserialize_untagged_variant(
     }
 }
 
+// The value of a variant deserialized as a unit variant, with any skipped
+// fields filled in from their defaults.
+fn unit_variant_value(params: &Parameters, variant: &Variant, cattrs: &attr::Container) -> Fragment {
+    let this = &params.this;
+    let variant_ident = &variant.ident;
+    let defaults = variant
+        .fields
+        .iter()
+        .map(|field| Expr(expr_is_missing(field, cattrs)));
+
+    match variant.style {
+        Style::Unit => quote_expr!(#this::#variant_ident),
+        Style::Newtype | Style::Tuple => quote_expr!(#this::#variant_ident(#(#defaults),*)),
+        Style::Struct => {
+            let idents = variant
+                .fields
+                .iter()
+                .map(|field| field.ident.clone().expect("struct variant has unnamed fields"));
+            quote_expr!(#this::#variant_ident { #(#idents: #defaults),* })
+        }
+    }
+}
+
 fn deserialize_externally_tagged_newtype_variant(
     variant_ident: &syn::Ident,
     params: &Parameters,
@@ -1237,15 +1384,36 @@ fn deserialize_untagged_newtype_variant(
     }
 }
 
+/// Index of the `#[serde(other)]` variant of an enum, which unknown variant
+/// names deserialize to.
+fn other_variant_index(variants: &[Variant]) -> Option<usize> {
+    variants
+        .iter()
+        .position(|variant| variant.attrs.other() && !variant.attrs.skip_deserializing())
+}
+
 fn deserialize_generated_identifier(
     fields: Vec<(String, Ident)>,
     cattrs: &attr::Container,
     is_variant: bool,
+    other_idx: Option<usize>,
 ) -> Fragment {
     let this = quote!(__Field);
-    let field_idents: &Vec<_> = &fields.iter().map(|&(_, ref ident)| ident).collect();
 
-    let (ignore_variant, fallthrough) = if is_variant || cattrs.deny_unknown_fields() {
+    // Aliases map more than one name to the same identifier.
+    let mut field_idents = Vec::new();
+    for &(_, ref ident) in &fields {
+        if !field_idents.contains(&ident) {
+            field_idents.push(ident);
+        }
+    }
+    let field_idents = &field_idents;
+
+    let (ignore_variant, fallthrough) = if let Some(other_idx) = other_idx {
+        let other_ident = field_i(other_idx);
+        let fallthrough = quote!(_serde::export::Ok(__Field::#other_ident));
+        (None, Some(fallthrough))
+    } else if is_variant || cattrs.deny_unknown_fields() {
         (None, None)
     } else {
         let ignore_variant = quote!(__ignore,);
@@ -1481,7 +1649,22 @@ fn deserialize_struct_visitor(
         }
     };
 
-    let field_visitor = deserialize_generated_identifier(field_names_idents, cattrs, false);
+    let aliases = fields
+        .iter()
+        .enumerate()
+        .filter(|&(_, field)| !field.attrs.skip_deserializing())
+        .flat_map(
+            |(i, field)| {
+                field
+                    .attrs
+                    .deserialize_aliases()
+                    .iter()
+                    .map(move |alias| (alias.clone(), field_i(i)))
+            },
+        );
+    let field_names_idents = field_names_idents.iter().cloned().chain(aliases).collect();
+
+    let field_visitor = deserialize_generated_identifier(field_names_idents, cattrs, false, None);
 
     let visit_map = deserialize_map(struct_path, params, fields, cattrs);
 
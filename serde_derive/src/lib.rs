@@ -14,6 +14,11 @@
 //!
 //! Please refer to [https://serde.rs/derive.html] for how to set this up.
 //!
+//! The generated impls refer to the `serde` crate through their own `extern
+//! crate serde` item, so `serde` must be a dependency of the crate using the
+//! derive even if it is never imported there. Without it, compilation fails
+//! with "can't find crate for `serde`" pointing at the derive.
+//!
 //! [https://serde.rs/derive.html]: https://serde.rs/derive.html
 
 #![doc(html_root_url = "https://docs.rs/serde_derive/1.0.9")]
@@ -27,6 +27,9 @@ pub fn expand_derive_serialize(input: &syn::DeriveInput) -> Result<Tokens, Strin
     let (impl_generics, ty_generics, where_clause) = params.generics.split_for_impl();
     let dummy_const = Ident::new(format!("_IMPL_SERIALIZE_FOR_{}", ident));
     let body = Stmts(serialize_body(&cont, &params));
+    let adapt_serializer = cont.attrs
+        .with_serializer()
+        .map(|path| quote!(let __serializer = #path(__serializer);));
 
     let impl_block = if let Some(remote) = cont.attrs.remote() {
         quote! {
@@ -34,6 +37,7 @@ pub fn expand_derive_serialize(input: &syn::DeriveInput) -> Result<Tokens, Strin
                 fn serialize<__S>(__self: &#remote #ty_generics, __serializer: __S) -> _serde::export::Result<__S::Ok, __S::Error>
                     where __S: _serde::Serializer
                 {
+                    #adapt_serializer
                     #body
                 }
             }
@@ -45,17 +49,42 @@ pub fn expand_derive_serialize(input: &syn::DeriveInput) -> Result<Tokens, Strin
                 fn serialize<__S>(&self, __serializer: __S) -> _serde::export::Result<__S::Ok, __S::Error>
                     where __S: _serde::Serializer
                 {
+                    #adapt_serializer
                     #body
                 }
             }
         }
     };
 
+    let partial_block = if cont.attrs.partial() {
+        Some(serialize_partial(&cont, &params))
+    } else {
+        None
+    };
+
+    let field_info_block = if cont.attrs.emit_field_info() {
+        Some(field_info(&cont))
+    } else {
+        None
+    };
+
+    let field_docs_block = field_docs(&cont);
+
+    let is_empty_block = if cont.attrs.skip_if_all_fields_skipped() {
+        Some(is_empty(&cont))
+    } else {
+        None
+    };
+
     let generated = quote! {
         #[allow(non_upper_case_globals, unused_attributes, unused_qualifications)]
         const #dummy_const: () = {
             extern crate serde as _serde;
             #impl_block
+            #partial_block
+            #field_info_block
+            #field_docs_block
+            #is_empty_block
         };
     };
     Ok(generated)
@@ -88,6 +117,12 @@ struct Parameters {
 
     /// Type has a `serde(remote = "...")` attribute.
     is_remote: bool,
+
+    /// Handling of non-finite float fields from `serde(non_finite = "...")`.
+    non_finite: Option<attr::NonFinite>,
+
+    /// Order of named fields from `serde(field_order = "...")`.
+    field_order: Option<attr::FieldOrder>,
 }
 
 impl Parameters {
@@ -111,6 +146,8 @@ impl Parameters {
             this: this,
             generics: generics,
             is_remote: is_remote,
+            non_finite: cont.attrs.non_finite(),
+            field_order: cont.attrs.field_order(),
         }
     }
 
@@ -153,6 +190,8 @@ fn needs_serialize_bound(attrs: &attr::Field) -> bool {
 fn serialize_body(cont: &Container, params: &Parameters) -> Fragment {
     if let Some(into_type) = cont.attrs.into_type() {
         serialize_into(params, into_type)
+    } else if let (true, &Body::Struct(_, ref fields)) = (cont.attrs.transparent(), &cont.body) {
+        serialize_transparent(params, &fields[0])
     } else {
         match cont.body {
             Body::Enum(ref variants) => serialize_enum(params, variants, &cont.attrs),
@@ -160,16 +199,28 @@ fn serialize_body(cont: &Container, params: &Parameters) -> Fragment {
                 if fields.iter().any(|field| field.ident.is_none()) {
                     panic!("struct has unnamed fields");
                 }
-                serialize_struct(params, fields, &cont.attrs)
+                if let Some(name) = cont.attrs.scalar_field() {
+                    serialize_scalar_field(params, fields, name)
+                } else if cont.attrs.as_flags() {
+                    serialize_struct_as_flags(params, fields)
+                } else {
+                    serialize_struct(params, fields, &cont.attrs)
+                }
             }
             Body::Struct(Style::Tuple, ref fields) => {
                 if fields.iter().any(|field| field.ident.is_some()) {
                     panic!("tuple struct has named fields");
                 }
-                serialize_tuple_struct(params, fields, &cont.attrs)
+                match cont.attrs.tuple_names() {
+                    Some(names) => serialize_tuple_struct_as_map(params, fields, names),
+                    None => serialize_tuple_struct(params, fields, &cont.attrs),
+                }
             }
             Body::Struct(Style::Newtype, ref fields) => {
-                serialize_newtype_struct(params, &fields[0], &cont.attrs)
+                match cont.attrs.tuple_names() {
+                    Some(names) => serialize_tuple_struct_as_map(params, fields, names),
+                    None => serialize_newtype_struct(params, &fields[0], &cont.attrs),
+                }
             }
             Body::Struct(Style::Unit, _) => serialize_unit_struct(&cont.attrs),
         }
@@ -185,7 +236,49 @@ fn serialize_into(params: &Parameters, into_type: &syn::Ty) -> Fragment {
     }
 }
 
+fn serialize_transparent(params: &Parameters, field: &Field) -> Fragment {
+    let mut field_expr = match field.ident {
+        Some(ref ident) => get_field(params, field, ident.clone()),
+        None => get_field(params, field, 0),
+    };
+    field_expr = wrap_serialize_field(params, field, field_expr);
+
+    quote_expr! {
+        _serde::Serialize::serialize(#field_expr, __serializer)
+    }
+}
+
+// With `#[serde(scalar_field = "...")]` the struct is serialized as the value
+// of that one field. The other fields are not serialized at all.
+fn serialize_scalar_field(params: &Parameters, fields: &[Field], name: &str) -> Fragment {
+    let field = fields
+        .iter()
+        .find(|field| field.ident.as_ref().map_or(false, |ident| ident == name))
+        .expect("scalar_field names a field of the struct");
+    let ident = field.ident.clone().unwrap();
+
+    let mut field_expr = get_field(params, field, ident);
+    field_expr = wrap_serialize_field(params, field, field_expr);
+
+    quote_expr! {
+        _serde::Serialize::serialize(#field_expr, __serializer)
+    }
+}
+
 fn serialize_unit_struct(cattrs: &attr::Container) -> Fragment {
+    if cattrs.unit_as_null() {
+        return quote_expr! {
+            _serde::Serializer::serialize_none(__serializer)
+        };
+    }
+
+    if cattrs.as_empty_map() {
+        return quote_block! {
+            let __serde_state = try!(_serde::Serializer::serialize_map(__serializer, _serde::export::Some(0)));
+            _serde::ser::SerializeMap::end(__serde_state)
+        };
+    }
+
     let type_name = cattrs.name().serialize_name();
 
     quote_expr! {
@@ -198,11 +291,18 @@ fn serialize_newtype_struct(
     field: &Field,
     cattrs: &attr::Container,
 ) -> Fragment {
-    let type_name = cattrs.name().serialize_name();
+    let type_name = match cattrs.newtype_name() {
+        Some(name) => name.to_owned(),
+        None => cattrs.name().serialize_name(),
+    };
 
     let mut field_expr = get_field(params, field, 0);
-    if let Some(path) = field.attrs.serialize_with() {
-        field_expr = wrap_serialize_with(params, field.ty, path, field_expr);
+    field_expr = wrap_serialize_field(params, field, field_expr);
+
+    if cattrs.newtype_option() == Some(attr::NewtypeOption::Inline) {
+        return quote_expr! {
+            _serde::Serialize::serialize(#field_expr, __serializer)
+        };
     }
 
     quote_expr! {
@@ -215,6 +315,10 @@ fn serialize_tuple_struct(
     fields: &[Field],
     cattrs: &attr::Container,
 ) -> Fragment {
+    if fields.iter().any(|field| field.attrs.flatten()) {
+        return serialize_tuple_struct_as_seq(params, fields);
+    }
+
     let serialize_stmts = serialize_tuple_struct_visitor(
         fields,
         params,
@@ -233,45 +337,499 @@ fn serialize_tuple_struct(
     }
 }
 
+// A tuple struct containing `#[serde(flatten)]` fields is serialized as a
+// sequence of unknown length so that the elements of the flattened fields
+// can be spliced in alongside its own elements.
+fn serialize_tuple_struct_as_seq(params: &Parameters, fields: &[Field]) -> Fragment {
+    let serialize_stmts = serialize_tuple_struct_visitor(
+        fields,
+        params,
+        false,
+        quote!(_serde::ser::SerializeSeq::serialize_element),
+    );
+
+    let gate = human_readable_gate(fields);
+
+    quote_block! {
+        #gate
+        let mut __serde_state = try!(_serde::Serializer::serialize_seq(__serializer, _serde::export::None));
+        #(#serialize_stmts)*
+        _serde::ser::SerializeSeq::end(__serde_state)
+    }
+}
+
+// With `#[serde(tuple_names = "...")]` the elements of a tuple struct are
+// serialized as the entries of a map keyed by the given names.
+fn serialize_tuple_struct_as_map(
+    params: &Parameters,
+    fields: &[Field],
+    names: &[String],
+) -> Fragment {
+    let serialized = fields
+        .iter()
+        .zip(names)
+        .enumerate()
+        .filter(|&(_, (field, _))| !field.attrs.skip_serializing());
+
+    let mut len = quote!(0);
+    let mut skips = Vec::new();
+    let mut serialize_entries = Vec::new();
+    for (i, (field, name)) in serialized {
+        let mut field_expr = get_field(params, field, i);
+        let skip = skip_serializing_if(field, &field_expr).map(
+            |skip| {
+                let var = Ident::new(format!("__serde_skip_{}", i));
+                skips.push(quote!(let #var: bool = #skip;));
+                quote!(#var)
+            },
+        );
+
+        field_expr = wrap_serialize_field(params, field, field_expr);
+
+        let ser = quote! {
+            try!(_serde::ser::SerializeMap::serialize_entry(&mut __serde_state, #name, #field_expr));
+        };
+
+        match skip {
+            None => {
+                len = quote!(#len + 1);
+                serialize_entries.push(ser);
+            }
+            Some(skip) => {
+                len = quote!(#len + if #skip { 0 } else { 1 });
+                serialize_entries.push(quote!(if !#skip { #ser }));
+            }
+        }
+    }
+
+    let let_mut = mut_if(!serialize_entries.is_empty());
+
+    quote_block! {
+        #(#skips)*
+        let #let_mut __serde_state = try!(_serde::Serializer::serialize_map(__serializer, _serde::export::Some(#len)));
+        #(#serialize_entries)*
+        _serde::ser::SerializeMap::end(__serde_state)
+    }
+}
+
 fn serialize_struct(params: &Parameters, fields: &[Field], cattrs: &attr::Container) -> Fragment {
     assert!(fields.len() as u64 <= u32::MAX as u64);
 
+    if fields.iter().any(|field| field.attrs.flatten()) || cattrs.key_transform().is_some() {
+        return serialize_struct_as_map(params, fields, cattrs);
+    }
+
     let serialize_fields = serialize_struct_visitor(
         fields,
         params,
         false,
+        cattrs.intern_keys(),
+        None,
         quote!(_serde::ser::SerializeStruct::serialize_field),
     );
 
     let type_name = cattrs.name().serialize_name();
+    let keys = interned_keys(fields, cattrs);
 
     let mut serialized_fields = fields
         .iter()
         .filter(|&field| !field.attrs.skip_serializing())
         .peekable();
 
-    let let_mut = mut_if(serialized_fields.peek().is_some());
+    let computed = cattrs.computed();
+    let serialize_tag = serialize_struct_tag(
+        cattrs,
+        None,
+        quote!(_serde::ser::SerializeStruct::serialize_field),
+    );
+    let let_mut = mut_if(
+        serialized_fields.peek().is_some() || !computed.is_empty() || serialize_tag.is_some(),
+    );
+
+    let len = serialized_len(serialized_fields, computed.len() + serialize_tag.iter().count());
+
+    let serialize_computed = serialize_computed_fields(
+        params,
+        computed,
+        None,
+        quote!(_serde::ser::SerializeStruct::serialize_field),
+    );
+
+    let gates = serializer_gates(fields);
+    let skips = cached_skips(params, fields, false);
+
+    quote_block! {
+        #keys
+        #(#gates)*
+        #(#skips)*
+        let #let_mut __serde_state = try!(_serde::Serializer::serialize_struct(__serializer, #type_name, #len));
+        #serialize_tag
+        #(#serialize_fields)*
+        #(#serialize_computed)*
+        _serde::ser::SerializeStruct::end(__serde_state)
+    }
+}
+
+// A struct containing `#[serde(flatten)]` fields is serialized as a map of
+// unknown length so that the entries of the flattened fields can be spliced
+// in alongside its own fields. Entries are emitted in field declaration order,
+// with the entries of each flattened field in the order its own `Serialize`
+// impl produces them, followed by any computed fields. Adding, removing or
+// skipping a regular field therefore never reorders the flattened entries
+// relative to each other or to the regular fields around them. A struct with
+// `#[serde(key_transform)]` is serialized the same way because
+// `SerializeStruct` only accepts keys that are `&'static str`.
+fn serialize_struct_as_map(
+    params: &Parameters,
+    fields: &[Field],
+    cattrs: &attr::Container,
+) -> Fragment {
+    let serialize_fields = serialize_struct_visitor(
+        fields,
+        params,
+        false,
+        cattrs.intern_keys(),
+        cattrs.key_transform(),
+        quote!(_serde::ser::SerializeMap::serialize_entry),
+    );
+
+    let keys = interned_keys(fields, cattrs);
+
+    let serialize_computed = serialize_computed_fields(
+        params,
+        cattrs.computed(),
+        cattrs.key_transform(),
+        quote!(_serde::ser::SerializeMap::serialize_entry),
+    );
+
+    let serialize_tag = serialize_struct_tag(
+        cattrs,
+        cattrs.key_transform(),
+        quote!(_serde::ser::SerializeMap::serialize_entry),
+    );
+
+    let gates = serializer_gates(fields);
+    let skips = cached_skips(params, fields, false);
+
+    quote_block! {
+        #keys
+        #(#gates)*
+        #(#skips)*
+        let mut __serde_state = try!(_serde::Serializer::serialize_map(__serializer, _serde::export::None));
+        #serialize_tag
+        #(#serialize_fields)*
+        #(#serialize_computed)*
+        _serde::ser::SerializeMap::end(__serde_state)
+    }
+}
+
+// The constant field of a struct with `#[serde(tag = "...")]` comes before all
+// of the struct's own fields.
+fn serialize_struct_tag(
+    cattrs: &attr::Container,
+    key_transform: Option<&syn::Path>,
+    func: Tokens,
+) -> Option<Tokens> {
+    cattrs
+        .struct_tag()
+        .map(
+            |(tag, value)| {
+                let key = transform_key(quote!(#tag), key_transform);
+                quote! {
+                    try!(#func(&mut __serde_state, #key, #value));
+                }
+            },
+        )
+}
+
+// Computed fields are appended after all of the struct's own fields.
+fn serialize_computed_fields(
+    params: &Parameters,
+    computed: &[attr::Computed],
+    key_transform: Option<&syn::Path>,
+    func: Tokens,
+) -> Vec<Tokens> {
+    let self_var = &params.self_var;
+
+    computed
+        .iter()
+        .map(
+            |c| {
+                let key = c.name();
+                let key = transform_key(quote!(#key), key_transform);
+                let value = c.value();
+                quote! {
+                    try!(#func(&mut __serde_state, #key, &#value(#self_var)));
+                }
+            },
+        )
+        .collect()
+}
+
+// Generates an inherent `FIELDS` const for `#[serde(emit_field_info)]` that
+// pairs the serialized name of every serialized field with its Rust type as
+// written in the struct definition.
+fn field_info(cont: &Container) -> Tokens {
+    let fields = match cont.body {
+        Body::Struct(Style::Struct, ref fields) => fields,
+        _ => unreachable!("checked in serde_derive_internals"),
+    };
+
+    let entries = fields
+        .iter()
+        .filter(|field| !field.attrs.skip_serializing())
+        .map(
+            |field| {
+                let name = field.attrs.name().serialize_name();
+                let ty = field.ty;
+                let ty = type_to_string(&quote!(#ty));
+                quote!((#name, #ty))
+            },
+        );
+
+    let ident = &cont.ident;
+    let (impl_generics, ty_generics, where_clause) = cont.generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            pub const FIELDS: &'static [(&'static str, &'static str)] = &[#(#entries),*];
+        }
+    }
+}
+
+// Generates an inherent `FIELD_DOCS` const pairing the serialized name of every
+// serialized field that has a `#[serde(description = "...")]` with its
+// description. Nothing is generated if no field has a description.
+fn field_docs(cont: &Container) -> Option<Tokens> {
+    let fields = match cont.body {
+        Body::Struct(Style::Struct, ref fields) => fields,
+        _ => return None,
+    };
+
+    let entries: Vec<_> = fields
+        .iter()
+        .filter(|field| !field.attrs.skip_serializing())
+        .filter_map(
+            |field| {
+                field.attrs.description().map(
+                    |description| {
+                        let name = field.attrs.name().serialize_name();
+                        quote!((#name, #description))
+                    },
+                )
+            },
+        )
+        .collect();
+    if entries.is_empty() {
+        return None;
+    }
+
+    let ident = &cont.ident;
+    let (impl_generics, ty_generics, where_clause) = cont.generics.split_for_impl();
+
+    Some(
+        quote! {
+            impl #impl_generics #ident #ty_generics #where_clause {
+                pub const FIELD_DOCS: &'static [(&'static str, &'static str)] = &[#(#entries),*];
+            }
+        },
+    )
+}
+
+// Generates an inherent `__serde_is_empty` method for
+// `#[serde(skip_if_all_fields_skipped)]`. A field counts as skipped if it has
+// `skip_serializing` or its `skip_serializing_if` predicate holds. Predicates
+// of `skip_serializing_unless_serializer` need the serializer and are ignored.
+fn is_empty(cont: &Container) -> Tokens {
+    let fields = match cont.body {
+        Body::Struct(Style::Struct, ref fields) => fields,
+        _ => unreachable!("checked in serde_derive_internals"),
+    };
+
+    let skipped = fields
+        .iter()
+        .filter(|&field| !field.attrs.skip_serializing())
+        .map(
+            |field| {
+                let ident = field.ident.clone().expect("struct has unnamed fields");
+                let field_expr = quote!(&self.#ident);
+                skip_predicate(field, &field_expr).unwrap_or_else(|| quote!(false))
+            },
+        )
+        .fold(quote!(true), |all, skip| quote!(#all && #skip));
+
+    let ident = &cont.ident;
+    let (impl_generics, ty_generics, where_clause) = cont.generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            pub fn __serde_is_empty(&self) -> bool {
+                #skipped
+            }
+        }
+    }
+}
+
+// Tokens are printed separated by spaces, as in `Vec < & 'a str >`. Remove
+// the spaces that rustc would not print, giving `Vec<&'a str>`.
+fn type_to_string(tokens: &Tokens) -> String {
+    let mut out = String::new();
+    let mut iter = tokens.as_str().split(' ').peekable();
+    while let Some(token) = iter.next() {
+        out.push_str(token);
+        let next = match iter.peek() {
+            Some(next) => *next,
+            None => break,
+        };
+        let glued = match (token, next) {
+            (_, ">") | (_, ",") | (_, ";") | (_, ")") | (_, "]") | (_, "<") | (_, "::") => true,
+            ("<", _) | ("&", _) | ("(", _) | ("[", _) | ("::", _) | ("*", _) => true,
+            _ => false,
+        };
+        if !glued {
+            out.push(' ');
+        }
+    }
+    out
+}
+
+// Generates an inherent `serialize_fields` method for `#[serde(partial)]` that
+// serializes only the fields whose serialized name appears in `__fields`.
+// Names in `__fields` that do not belong to any field are ignored.
+fn serialize_partial(cont: &Container, params: &Parameters) -> Tokens {
+    let fields = match cont.body {
+        Body::Struct(Style::Struct, ref fields) => fields,
+        _ => unreachable!("checked in serde_derive_internals"),
+    };
+
+    let ident = &cont.ident;
+    let (impl_generics, ty_generics, where_clause) = params.generics.split_for_impl();
+    let body = Stmts(serialize_partial_struct(params, fields, &cont.attrs));
+
+    let self_arg = match cont.attrs.remote() {
+        Some(remote) => quote!(__self: &#remote #ty_generics),
+        None => quote!(&self),
+    };
+
+    quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            pub fn serialize_fields<__S>(#self_arg, __serializer: __S, __fields: &[&str]) -> _serde::export::Result<__S::Ok, __S::Error>
+                where __S: _serde::Serializer
+            {
+                #body
+            }
+        }
+    }
+}
+
+fn serialize_partial_struct(
+    params: &Parameters,
+    fields: &[Field],
+    cattrs: &attr::Container,
+) -> Fragment {
+    assert!(fields.len() as u64 <= u32::MAX as u64);
+
+    let serialize_fields = serialize_struct_visitor(
+        fields,
+        params,
+        false,
+        cattrs.intern_keys(),
+        None,
+        quote!(_serde::ser::SerializeStruct::serialize_field),
+    );
+
+    let type_name = cattrs.name().serialize_name();
+    let keys = interned_keys(fields, cattrs);
+
+    // Same order as the statements from `serialize_struct_visitor`.
+    let serialized_fields: Vec<_> = serialized_fields_in_order(params, fields)
+        .into_iter()
+        .map(|(_, field)| field)
+        .collect();
+
+    let let_mut = mut_if(!serialized_fields.is_empty());
+
+    let serialize_fields = serialized_fields
+        .iter()
+        .zip(serialize_fields)
+        .map(
+            |(field, ser)| {
+                let key_expr = field_key(field);
+                quote!(if __fields.contains(&#key_expr) { #ser })
+            },
+        );
 
-    let len = serialized_fields
+    let len: Vec<_> = serialized_fields
+        .iter()
         .map(
-            |field| match field.attrs.skip_serializing_if() {
-                None => quote!(1),
-                Some(path) => {
-                    let ident = field.ident.clone().expect("struct has unnamed fields");
-                    let field_expr = get_field(params, field, ident);
-                    quote!(if #path(#field_expr) { 0 } else { 1 })
+            |field| {
+                let key_expr = field_key(field);
+                match cached_skip(field) {
+                    None => quote!(if __fields.contains(&#key_expr) { 1 } else { 0 }),
+                    Some(skip) => quote!(if !__fields.contains(&#key_expr) || #skip { 0 } else { 1 }),
                 }
             },
         )
-        .fold(quote!(0), |sum, expr| quote!(#sum + #expr));
+        .collect();
+    let len = sum(&len);
+
+    let gates = serializer_gates(fields);
+    let skips = cached_skips(params, fields, false);
 
     quote_block! {
+        #keys
+        #(#gates)*
+        #(#skips)*
         let #let_mut __serde_state = try!(_serde::Serializer::serialize_struct(__serializer, #type_name, #len));
         #(#serialize_fields)*
         _serde::ser::SerializeStruct::end(__serde_state)
     }
 }
 
+fn serialize_struct_as_flags(params: &Parameters, fields: &[Field]) -> Fragment {
+    let serialized_fields: Vec<_> = fields
+        .iter()
+        .filter(|&field| !field.attrs.skip_serializing())
+        .map(
+            |field| {
+                let ident = field.ident.clone().expect("struct has unnamed fields");
+                (field_key(field), get_field(params, field, ident))
+            },
+        )
+        .collect();
+
+    let let_mut = mut_if(!serialized_fields.is_empty());
+
+    let len: Vec<_> = serialized_fields
+        .iter()
+        .map(|&(_, ref field_expr)| quote!(if *#field_expr { 1 } else { 0 }))
+        .collect();
+    let len = sum(&len);
+
+    let serialize_names = serialized_fields
+        .iter()
+        .map(
+            |&(ref key_expr, ref field_expr)| {
+                quote! {
+                    if *#field_expr {
+                        try!(_serde::ser::SerializeSeq::serialize_element(&mut __serde_state, #key_expr));
+                    }
+                }
+            },
+        );
+
+    let gates = serializer_gates(fields);
+
+    quote_block! {
+        #(#gates)*
+        let #let_mut __serde_state = try!(_serde::Serializer::serialize_seq(
+            __serializer,
+            _serde::export::Some(#len)));
+        #(#serialize_names)*
+        _serde::ser::SerializeSeq::end(__serde_state)
+    }
+}
+
 fn serialize_enum(params: &Parameters, variants: &[Variant], cattrs: &attr::Container) -> Fragment {
     assert!(variants.len() as u64 <= u32::MAX as u64);
 
@@ -328,25 +886,35 @@ fn serialize_variant(
         }
     } else {
         // variant wasn't skipped
-        let case = match variant.style {
-            Style::Unit => {
+        let case = match (variant.style, variant.represented_style()) {
+            (Style::Unit, _) => {
                 quote! {
                     #this::#variant_ident
                 }
             }
-            Style::Newtype => {
+            (Style::Tuple, Style::Unit) => {
+                quote! {
+                    #this::#variant_ident(..)
+                }
+            }
+            (Style::Struct, Style::Unit) => {
+                quote! {
+                    #this::#variant_ident { .. }
+                }
+            }
+            (Style::Newtype, _) => {
                 quote! {
                     #this::#variant_ident(ref __field0)
                 }
             }
-            Style::Tuple => {
+            (Style::Tuple, _) => {
                 let field_names =
                     (0..variant.fields.len()).map(|i| Ident::new(format!("__field{}", i)));
                 quote! {
                     #this::#variant_ident(#(ref #field_names),*)
                 }
             }
-            Style::Struct => {
+            (Style::Struct, _) => {
                 let fields = variant
                     .fields
                     .iter()
@@ -357,16 +925,36 @@ fn serialize_variant(
                                 .expect("struct variant has unnamed fields")
                         },
                     );
+                let bindings = variant.fields.iter().map(variant_field_binding);
                 quote! {
-                    #this::#variant_ident { #(ref #fields),* }
+                    #this::#variant_ident { #(#fields: ref #bindings),* }
                 }
             }
         };
 
         let body = Match(
             match *cattrs.tag() {
+                _ if variant.attrs.unit_as_null() => {
+                    quote_expr!(_serde::Serializer::serialize_none(__serializer))
+                }
+                // The only variant of a transparent enum is serialized without
+                // its name, the same as in an untagged enum.
+                _ if cattrs.transparent() => serialize_untagged_variant(params, variant, cattrs),
+                attr::EnumTag::External if cattrs.external_as_map() => {
+                    serialize_external_as_map_variant(params, variant)
+                }
                 attr::EnumTag::External => {
-                    serialize_externally_tagged_variant(params, variant, variant_index, cattrs)
+                    match variant.attrs.tag() {
+                        Some(tag) => serialize_internally_tagged_variant(params, variant, cattrs, tag),
+                        None => {
+                            serialize_externally_tagged_variant(
+                                params,
+                                variant,
+                                variant_index,
+                                cattrs,
+                            )
+                        }
+                    }
                 }
                 attr::EnumTag::Internal { ref tag } => {
                     serialize_internally_tagged_variant(params, variant, cattrs, tag)
@@ -394,7 +982,7 @@ fn serialize_externally_tagged_variant(
     let type_name = cattrs.name().serialize_name();
     let variant_name = variant.attrs.name().serialize_name();
 
-    match variant.style {
+    match variant.represented_style() {
         Style::Unit => {
             quote_expr! {
                 _serde::Serializer::serialize_unit_variant(
@@ -408,9 +996,7 @@ fn serialize_externally_tagged_variant(
         Style::Newtype => {
             let field = &variant.fields[0];
             let mut field_expr = quote!(__field0);
-            if let Some(path) = field.attrs.serialize_with() {
-                field_expr = wrap_serialize_with(params, field.ty, path, field_expr);
-            }
+            field_expr = wrap_serialize_field(params, field, field_expr);
 
             quote_expr! {
                 _serde::Serializer::serialize_newtype_variant(
@@ -433,6 +1019,18 @@ fn serialize_externally_tagged_variant(
                 &variant.fields,
             )
         }
+        Style::Struct if variant.fields.iter().any(|field| field.attrs.flatten()) => {
+            serialize_external_as_map_variant(params, variant)
+        }
+        Style::Struct if variant.attrs.as_seq() => {
+            serialize_struct_variant_as_seq(
+                variant_index,
+                variant_name,
+                params,
+                &variant.fields,
+                &type_name,
+            )
+        }
         Style::Struct => {
             serialize_struct_variant(
                 StructVariant::ExternallyTagged {
@@ -447,6 +1045,57 @@ fn serialize_externally_tagged_variant(
     }
 }
 
+// With `#[serde(as_seq)]` the fields of a struct variant are serialized
+// positionally, as if it were a tuple variant.
+fn serialize_struct_variant_as_seq(
+    variant_index: u32,
+    variant_name: String,
+    params: &Parameters,
+    fields: &[Field],
+    name: &str,
+) -> Fragment {
+    let mut len = quote!(0);
+    let mut serialize_fields = Vec::new();
+    for field in fields.iter().filter(|&field| !field.attrs.skip_serializing()) {
+        let binding = variant_field_binding(field);
+        let mut field_expr = quote!(#binding);
+        let skip = cached_skip(field);
+
+        field_expr = wrap_serialize_field(params, field, field_expr);
+
+        let ser = quote! {
+            try!(_serde::ser::SerializeTupleVariant::serialize_field(&mut __serde_state, #field_expr));
+        };
+
+        match skip {
+            None => {
+                len = quote!(#len + 1);
+                serialize_fields.push(ser);
+            }
+            Some(skip) => {
+                len = quote!(#len + if #skip { 0 } else { 1 });
+                serialize_fields.push(quote!(if !#skip { #ser }));
+            }
+        }
+    }
+
+    let let_mut = mut_if(!serialize_fields.is_empty());
+    let skips = cached_skips(params, fields, true);
+
+    quote_block! {
+        #(#skips)*
+        let #let_mut __serde_state = try!(_serde::Serializer::serialize_tuple_variant(
+            __serializer,
+            #name,
+            #variant_index,
+            #variant_name,
+            #len,
+        ));
+        #(#serialize_fields)*
+        _serde::ser::SerializeTupleVariant::end(__serde_state)
+    }
+}
+
 fn serialize_internally_tagged_variant(
     params: &Parameters,
     variant: &Variant,
@@ -459,7 +1108,7 @@ fn serialize_internally_tagged_variant(
     let enum_ident_str = params.type_name();
     let variant_ident_str = variant.ident.as_ref();
 
-    match variant.style {
+    match variant.represented_style() {
         Style::Unit => {
             quote_block! {
                 let mut __struct = try!(_serde::Serializer::serialize_struct(
@@ -469,12 +1118,27 @@ fn serialize_internally_tagged_variant(
                 _serde::ser::SerializeStruct::end(__struct)
             }
         }
-        Style::Newtype => {
+        Style::Newtype if variant.attrs.flatten() => {
             let field = &variant.fields[0];
             let mut field_expr = quote!(__field0);
-            if let Some(path) = field.attrs.serialize_with() {
-                field_expr = wrap_serialize_with(params, field.ty, path, field_expr);
+            field_expr = wrap_serialize_field(params, field, field_expr);
+
+            quote_block! {
+                let __serde_human_readable = _serde::Serializer::is_human_readable(&__serializer);
+                let mut __serde_state = try!(_serde::Serializer::serialize_map(
+                    __serializer, _serde::export::None));
+                try!(_serde::ser::SerializeMap::serialize_entry(
+                    &mut __serde_state, #tag, #variant_name));
+                try!(_serde::Serialize::serialize(
+                    #field_expr,
+                    _serde::private::ser::FlatMapSerializer(&mut __serde_state, __serde_human_readable)));
+                _serde::ser::SerializeMap::end(__serde_state)
             }
+        }
+        Style::Newtype => {
+            let field = &variant.fields[0];
+            let mut field_expr = quote!(__field0);
+            field_expr = wrap_serialize_field(params, field, field_expr);
 
             quote_expr! {
                 _serde::private::ser::serialize_tagged_newtype(
@@ -509,27 +1173,68 @@ fn serialize_adjacently_tagged_variant(
     tag: &str,
     content: &str,
 ) -> Fragment {
-    let this = &params.this;
     let type_name = cattrs.name().serialize_name();
     let variant_name = variant.attrs.name().serialize_name();
 
-    let inner = Stmts(
-        match variant.style {
-            Style::Unit => {
-                return quote_block! {
-                    let mut __struct = try!(_serde::Serializer::serialize_struct(
-                        __serializer, #type_name, 1));
-                    try!(_serde::ser::SerializeStruct::serialize_field(
-                        &mut __struct, #tag, #variant_name));
-                    _serde::ser::SerializeStruct::end(__struct)
-                };
+    match variant.represented_style() {
+        Style::Unit => {
+            quote_block! {
+                let mut __struct = try!(_serde::Serializer::serialize_struct(
+                    __serializer, #type_name, 1));
+                try!(_serde::ser::SerializeStruct::serialize_field(
+                    &mut __struct, #tag, #variant_name));
+                _serde::ser::SerializeStruct::end(__struct)
             }
+        }
+        Style::Newtype | Style::Tuple | Style::Struct => {
+            let content_expr = wrap_variant_content(params, variant, &variant_name);
+            quote_block! {
+                let mut __struct = try!(_serde::Serializer::serialize_struct(
+                    __serializer, #type_name, 2));
+                try!(_serde::ser::SerializeStruct::serialize_field(
+                    &mut __struct, #tag, #variant_name));
+                try!(_serde::ser::SerializeStruct::serialize_field(
+                    &mut __struct, #content, #content_expr));
+                _serde::ser::SerializeStruct::end(__struct)
+            }
+        }
+    }
+}
+
+// With `#[serde(external_as_map)]` every variant is a map with the variant
+// name as its only key. The value of a unit variant is unit.
+fn serialize_external_as_map_variant(params: &Parameters, variant: &Variant) -> Fragment {
+    let variant_name = variant.attrs.name().serialize_name();
+
+    let content_expr = match variant.represented_style() {
+        Style::Unit => quote!(&()),
+        Style::Newtype | Style::Tuple | Style::Struct => {
+            wrap_variant_content(params, variant, &variant_name)
+        }
+    };
+
+    quote_block! {
+        let mut __serde_state = try!(_serde::Serializer::serialize_map(
+            __serializer, _serde::export::Some(1)));
+        try!(_serde::ser::SerializeMap::serialize_entry(
+            &mut __serde_state, #variant_name, #content_expr));
+        _serde::ser::SerializeMap::end(__serde_state)
+    }
+}
+
+// Wraps references to the fields of a non-unit variant, bound by the match arm,
+// in a value that serializes them the way an untagged enum would. Used for the
+// content of adjacently tagged and `external_as_map` variants.
+fn wrap_variant_content(params: &Parameters, variant: &Variant, variant_name: &str) -> Tokens {
+    let this = &params.this;
+
+    let inner = Stmts(
+        match variant.represented_style() {
+            Style::Unit => unreachable!(),
             Style::Newtype => {
                 let field = &variant.fields[0];
                 let mut field_expr = quote!(__field0);
-                if let Some(path) = field.attrs.serialize_with() {
-                    field_expr = wrap_serialize_with(params, field.ty, path, field_expr);
-                }
+                field_expr = wrap_serialize_field(params, field, field_expr);
 
                 quote_expr! {
                     _serde::Serialize::serialize(#field_expr, __serializer)
@@ -543,7 +1248,7 @@ fn serialize_adjacently_tagged_variant(
                     StructVariant::Untagged,
                     params,
                     &variant.fields,
-                    &variant_name,
+                    variant_name,
                 )
             }
         },
@@ -558,19 +1263,7 @@ fn serialize_adjacently_tagged_variant(
                 .map(|i| Ident::new(format!("__field{}", i)))
                 .collect()
         }
-        Style::Struct => {
-            variant
-                .fields
-                .iter()
-                .map(
-                    |f| {
-                        f.ident
-                            .clone()
-                            .expect("struct variant has unnamed fields")
-                    },
-                )
-                .collect()
-        }
+        Style::Struct => variant.fields.iter().map(variant_field_binding).collect(),
     };
 
     let (_, ty_generics, where_clause) = params.generics.split_for_impl();
@@ -578,13 +1271,13 @@ fn serialize_adjacently_tagged_variant(
     let wrapper_generics = bound::with_lifetime_bound(&params.generics, "'__a");
     let (wrapper_impl_generics, wrapper_ty_generics, _) = wrapper_generics.split_for_impl();
 
-    quote_block! {
-        struct __AdjacentlyTagged #wrapper_generics #where_clause {
+    quote!({
+        struct __VariantContent #wrapper_generics #where_clause {
             data: (#(&'__a #fields_ty,)*),
             phantom: _serde::export::PhantomData<#this #ty_generics>,
         }
 
-        impl #wrapper_impl_generics _serde::Serialize for __AdjacentlyTagged #wrapper_ty_generics #where_clause {
+        impl #wrapper_impl_generics _serde::Serialize for __VariantContent #wrapper_ty_generics #where_clause {
             fn serialize<__S>(&self, __serializer: __S) -> _serde::export::Result<__S::Ok, __S::Error>
                 where __S: _serde::Serializer
             {
@@ -593,17 +1286,11 @@ fn serialize_adjacently_tagged_variant(
             }
         }
 
-        let mut __struct = try!(_serde::Serializer::serialize_struct(
-            __serializer, #type_name, 2));
-        try!(_serde::ser::SerializeStruct::serialize_field(
-            &mut __struct, #tag, #variant_name));
-        try!(_serde::ser::SerializeStruct::serialize_field(
-            &mut __struct, #content, &__AdjacentlyTagged {
-                data: (#(#fields_ident,)*),
-                phantom: _serde::export::PhantomData::<#this #ty_generics>,
-            }));
-        _serde::ser::SerializeStruct::end(__struct)
-    }
+        &__VariantContent {
+            data: (#(#fields_ident,)*),
+            phantom: _serde::export::PhantomData::<#this #ty_generics>,
+        }
+    })
 }
 
 fn serialize_untagged_variant(
@@ -611,7 +1298,7 @@ fn serialize_untagged_variant(
     variant: &Variant,
     cattrs: &attr::Container,
 ) -> Fragment {
-    match variant.style {
+    match variant.represented_style() {
         Style::Unit => {
             quote_expr! {
                 _serde::Serializer::serialize_unit(__serializer)
@@ -620,9 +1307,7 @@ fn serialize_untagged_variant(
         Style::Newtype => {
             let field = &variant.fields[0];
             let mut field_expr = quote!(__field0);
-            if let Some(path) = field.attrs.serialize_with() {
-                field_expr = wrap_serialize_with(params, field.ty, path, field_expr);
-            }
+            field_expr = wrap_serialize_field(params, field, field_expr);
 
             quote_expr! {
                 _serde::Serialize::serialize(#field_expr, __serializer)
@@ -706,6 +1391,10 @@ fn serialize_struct_variant<'a>(
     fields: &[Field],
     name: &str,
 ) -> Fragment {
+    if fields.iter().any(|field| field.attrs.flatten()) {
+        return serialize_struct_variant_as_map(context, params, fields);
+    }
+
     let method = match context {
         StructVariant::ExternallyTagged { .. } => {
             quote!(_serde::ser::SerializeStructVariant::serialize_field)
@@ -714,7 +1403,7 @@ fn serialize_struct_variant<'a>(
         StructVariant::Untagged => quote!(_serde::ser::SerializeStruct::serialize_field),
     };
 
-    let serialize_fields = serialize_struct_visitor(fields, params, true, method);
+    let serialize_fields = serialize_struct_visitor(fields, params, true, false, None, method);
 
     let mut serialized_fields = fields
         .iter()
@@ -723,18 +1412,9 @@ fn serialize_struct_variant<'a>(
 
     let let_mut = mut_if(serialized_fields.peek().is_some());
 
-    let len = serialized_fields
-        .map(
-            |field| {
-                let ident = field.ident.clone().expect("struct has unnamed fields");
+    let len = serialized_len(serialized_fields, 0);
 
-                match field.attrs.skip_serializing_if() {
-                    Some(path) => quote!(if #path(#ident) { 0 } else { 1 }),
-                    None => quote!(1),
-                }
-            },
-        )
-        .fold(quote!(0), |sum, expr| quote!(#sum + #expr));
+    let skips = cached_skips(params, fields, true);
 
     match context {
         StructVariant::ExternallyTagged {
@@ -742,6 +1422,7 @@ fn serialize_struct_variant<'a>(
             variant_name,
         } => {
             quote_block! {
+                #(#skips)*
                 let #let_mut __serde_state = try!(_serde::Serializer::serialize_struct_variant(
                     __serializer,
                     #name,
@@ -755,6 +1436,7 @@ fn serialize_struct_variant<'a>(
         }
         StructVariant::InternallyTagged { tag, variant_name } => {
             quote_block! {
+                #(#skips)*
                 let mut __serde_state = try!(_serde::Serializer::serialize_struct(
                     __serializer,
                     #name,
@@ -771,6 +1453,7 @@ fn serialize_struct_variant<'a>(
         }
         StructVariant::Untagged => {
             quote_block! {
+                #(#skips)*
                 let #let_mut __serde_state = try!(_serde::Serializer::serialize_struct(
                     __serializer,
                     #name,
@@ -783,6 +1466,50 @@ fn serialize_struct_variant<'a>(
     }
 }
 
+// Like a struct, a struct variant containing `#[serde(flatten)]` fields is
+// serialized as a map of unknown length. An externally tagged variant wraps
+// that map in a map with the variant name as its single key, see
+// `serialize_externally_tagged_variant`.
+fn serialize_struct_variant_as_map<'a>(
+    context: StructVariant<'a>,
+    params: &Parameters,
+    fields: &[Field],
+) -> Fragment {
+    let serialize_fields = serialize_struct_visitor(
+        fields,
+        params,
+        true,
+        false,
+        None,
+        quote!(_serde::ser::SerializeMap::serialize_entry),
+    );
+
+    let serialize_tag = match context {
+        StructVariant::ExternallyTagged { .. } => unreachable!(),
+        StructVariant::InternallyTagged { tag, variant_name } => {
+            Some(
+                quote! {
+                    try!(_serde::ser::SerializeMap::serialize_entry(
+                        &mut __serde_state, #tag, #variant_name));
+                },
+            )
+        }
+        StructVariant::Untagged => None,
+    };
+
+    let skips = cached_skips(params, fields, true);
+    let gate = human_readable_gate(fields);
+
+    quote_block! {
+        #(#skips)*
+        #gate
+        let mut __serde_state = try!(_serde::Serializer::serialize_map(__serializer, _serde::export::None));
+        #serialize_tag
+        #(#serialize_fields)*
+        _serde::ser::SerializeMap::end(__serde_state)
+    }
+}
+
 fn serialize_tuple_struct_visitor(
     fields: &[Field],
     params: &Parameters,
@@ -801,17 +1528,20 @@ fn serialize_tuple_struct_visitor(
                     get_field(params, field, i)
                 };
 
-                let skip = field
-                    .attrs
-                    .skip_serializing_if()
-                    .map(|path| quote!(#path(#field_expr)));
+                let skip = skip_serializing_if(field, &field_expr);
 
-                if let Some(path) = field.attrs.serialize_with() {
-                    field_expr = wrap_serialize_with(params, field.ty, path, field_expr);
-                }
+                field_expr = wrap_serialize_field(params, field, field_expr);
 
-                let ser = quote! {
-                    try!(#func(&mut __serde_state, #field_expr));
+                let ser = if field.attrs.flatten() {
+                    quote! {
+                        try!(_serde::Serialize::serialize(
+                            #field_expr,
+                            _serde::private::ser::FlatSeqSerializer(&mut __serde_state, __serde_human_readable)));
+                    }
+                } else {
+                    quote! {
+                        try!(#func(&mut __serde_state, #field_expr));
+                    }
                 };
 
                 match skip {
@@ -823,37 +1553,83 @@ fn serialize_tuple_struct_visitor(
         .collect()
 }
 
+// The fields that are serialized, in the order given by
+// `#[serde(field_order = "...")]`, each paired with its index into
+// `__SERDE_KEYS`. The index follows declaration order, so it is taken before
+// the fields are reordered.
+fn serialized_fields_in_order<'a, 'b>(
+    params: &Parameters,
+    fields: &'a [Field<'b>],
+) -> Vec<(usize, &'a Field<'b>)> {
+    let mut fields: Vec<_> = fields
+        .iter()
+        .filter(|&field| !field.attrs.skip_serializing())
+        .enumerate()
+        .collect();
+
+    if let Some(attr::FieldOrder::CanonicalLength) = params.field_order {
+        fields.sort_by_key(
+            |&(_, field)| {
+                let name = field.attrs.name().serialize_name();
+                (name.len(), name)
+            },
+        );
+    }
+
+    fields
+}
+
 fn serialize_struct_visitor(
     fields: &[Field],
     params: &Parameters,
     is_enum: bool,
+    is_interned: bool,
+    key_transform: Option<&syn::Path>,
     func: Tokens,
 ) -> Vec<Tokens> {
-    fields
-        .iter()
-        .filter(|&field| !field.attrs.skip_serializing())
+    serialized_fields_in_order(params, fields)
+        .into_iter()
         .map(
-            |field| {
+            |(i, field)| {
                 let field_ident = field.ident.clone().expect("struct has unnamed field");
                 let mut field_expr = if is_enum {
-                    quote!(#field_ident)
+                    let binding = variant_field_binding(field);
+                    quote!(#binding)
                 } else {
                     get_field(params, field, field_ident)
                 };
 
-                let key_expr = field.attrs.name().serialize_name();
+                let key_expr = if is_interned {
+                    quote!(__SERDE_KEYS[#i])
+                } else {
+                    field_key(field)
+                };
+                let key_expr = transform_key(key_expr, key_transform);
 
-                let skip = field
-                    .attrs
-                    .skip_serializing_if()
-                    .map(|path| quote!(#path(#field_expr)));
+                let skip = cached_skip(field);
 
-                if let Some(path) = field.attrs.serialize_with() {
-                    field_expr = wrap_serialize_with(params, field.ty, path, field_expr)
-                }
+                let is_option = field.is_option();
+                field_expr = wrap_serialize_field(params, field, field_expr);
 
-                let ser = quote! {
-                    try!(#func(&mut __serde_state, #key_expr, #field_expr));
+                let ser = if !field.attrs.flatten() {
+                    quote! {
+                        try!(#func(&mut __serde_state, #key_expr, #field_expr));
+                    }
+                } else if is_option && field.attrs.serialize_with().is_none() {
+                    // A flattened `None` contributes no entries.
+                    quote! {
+                        if let _serde::export::Some(ref __flatten) = *#field_expr {
+                            try!(_serde::Serialize::serialize(
+                                __flatten,
+                                _serde::private::ser::FlatMapSerializer(&mut __serde_state, __serde_human_readable)));
+                        }
+                    }
+                } else {
+                    quote! {
+                        try!(_serde::Serialize::serialize(
+                            #field_expr,
+                            _serde::private::ser::FlatMapSerializer(&mut __serde_state, __serde_human_readable)));
+                    }
                 };
 
                 match skip {
@@ -865,6 +1641,250 @@ fn serialize_struct_visitor(
         .collect()
 }
 
+// With `#[serde(intern_keys)]` the keys of all serialized fields are emitted
+// once into a static array. Every serialization then passes the same key
+// addresses to the serializer, which may use them to cache per-key work.
+fn interned_keys(fields: &[Field], cattrs: &attr::Container) -> Option<Tokens> {
+    if !cattrs.intern_keys() {
+        return None;
+    }
+
+    let keys: Vec<_> = fields
+        .iter()
+        .filter(|&field| !field.attrs.skip_serializing())
+        .map(field_key)
+        .collect();
+    let len = keys.len();
+
+    Some(quote!(static __SERDE_KEYS: [&'static str; #len] = [#(#keys),*];))
+}
+
+// Expression that is true if the field should be skipped according to its
+// `skip_serializing_if` predicate. A closure predicate is first bound to a
+// function pointer taking the field's type so that its argument type can be
+// inferred. The result of a path predicate is bound to a `bool` local so that
+// a predicate returning anything else fails with a type mismatch on the
+// predicate itself rather than wherever the result ends up being used. Fields
+// with `skip_serializing_unless_serializer` are also skipped if their gate,
+// evaluated by `serializer_gates`, is false.
+fn skip_serializing_if(field: &Field, field_expr: &Tokens) -> Option<Tokens> {
+    let predicate = skip_predicate(field, field_expr);
+
+    let gate = field.attrs.skip_serializing_unless_serializer().map(|_| gate_var(field));
+
+    match (gate, predicate) {
+        (None, predicate) => predicate,
+        (Some(gate), None) => Some(quote!(!#gate)),
+        (Some(gate), Some(predicate)) => Some(quote!((!#gate || #predicate))),
+    }
+}
+
+// The call of the `skip_serializing_if` predicate of a field, if it has one.
+fn skip_predicate(field: &Field, field_expr: &Tokens) -> Option<Tokens> {
+    field
+        .attrs
+        .skip_serializing_if()
+        .map(
+            |predicate| match *predicate {
+                attr::SkipPredicate::Closure(ref closure) => {
+                    let ty = field.ty;
+                    quote!({
+                        let __skip: fn(&#ty) -> bool = #(#closure)*;
+                        __skip(#field_expr)
+                    })
+                }
+                attr::SkipPredicate::Path(ref path) => {
+                    quote!({
+                        let __skip: bool = #path(#field_expr);
+                        __skip
+                    })
+                }
+            },
+        )
+}
+
+// Evaluates the skip condition of every serialized field once, into a local
+// that is read both for the length passed to the serializer and when
+// serializing the field. See `cached_skip`.
+fn cached_skips(params: &Parameters, fields: &[Field], is_enum: bool) -> Vec<Tokens> {
+    fields
+        .iter()
+        .filter(|&field| !field.attrs.skip_serializing())
+        .filter_map(
+            |field| {
+                let ident = field.ident.clone().expect("struct has unnamed fields");
+                let field_expr = if is_enum {
+                    let binding = variant_field_binding(field);
+                    quote!(#binding)
+                } else {
+                    get_field(params, field, ident)
+                };
+                skip_serializing_if(field, &field_expr).map(
+                    |skip| {
+                        let var = skip_var(field);
+                        quote!(let #var: bool = #skip;)
+                    },
+                )
+            },
+        )
+        .collect()
+}
+
+// The number of entries serialized for the given fields plus `extra` entries
+// that are always present. Fields that cannot be skipped at runtime are
+// counted into a single literal, so a struct without skippable fields gets a
+// constant length.
+fn serialized_len<'a, 'b: 'a, I>(fields: I, extra: usize) -> Tokens
+where
+    I: Iterator<Item = &'a Field<'b>>,
+{
+    let mut count = extra;
+    let mut terms = Vec::new();
+    for field in fields {
+        match cached_skip(field) {
+            None => count += 1,
+            Some(skip) => terms.push(quote!(if #skip { 0 } else { 1 })),
+        }
+    }
+
+    if terms.is_empty() {
+        quote!(#count)
+    } else {
+        let skippable = sum(&terms);
+        quote!(#count + #skippable)
+    }
+}
+
+// Adds up the terms as a balanced tree, so that structs with hundreds of
+// fields do not produce an expression nested hundreds of levels deep.
+fn sum(terms: &[Tokens]) -> Tokens {
+    match terms.len() {
+        0 => quote!(0),
+        1 => terms[0].clone(),
+        n => {
+            let left = sum(&terms[..n / 2]);
+            let right = sum(&terms[n / 2..]);
+            quote!((#left + #right))
+        }
+    }
+}
+
+// Fields of a struct variant are bound by the match arm under these names
+// rather than their own, so that a field named like one of the generated
+// locals, such as `__serializer`, cannot shadow it.
+fn variant_field_binding(field: &Field) -> Ident {
+    let ident = field.ident.as_ref().expect("struct variant has unnamed fields");
+    Ident::new(format!("__field_{}", ident))
+}
+
+// The local holding the skip condition of a named field, if it has one.
+fn cached_skip(field: &Field) -> Option<Tokens> {
+    if field.attrs.skip_serializing_if().is_none() &&
+       field.attrs.skip_serializing_unless_serializer().is_none() {
+        return None;
+    }
+    let var = skip_var(field);
+    Some(quote!(#var))
+}
+
+fn skip_var(field: &Field) -> Ident {
+    let ident = field.ident.as_ref().expect("struct has unnamed fields");
+    Ident::new(format!("__serde_skip_{}", ident))
+}
+
+// The serializer is consumed when serialization starts, so the predicates of
+// `skip_serializing_unless_serializer` are evaluated up front into one local
+// per field. So is whether the serializer is human readable, see
+// `human_readable_gate`.
+fn serializer_gates(fields: &[Field]) -> Vec<Tokens> {
+    let mut gates = Vec::new();
+    gates.extend(human_readable_gate(fields));
+
+    for field in fields.iter().filter(|&field| !field.attrs.skip_serializing()) {
+        if let Some(path) = field.attrs.skip_serializing_unless_serializer() {
+            let gate = gate_var(field);
+            gates.push(quote!(let #gate: bool = #path(&__serializer);));
+        }
+    }
+
+    gates
+}
+
+// Whether the serializer is human readable is needed by fields with different
+// names depending on it, and by flattened fields, whose serializer forwards it
+// to the flattened type.
+fn human_readable_gate(fields: &[Field]) -> Option<Tokens> {
+    if fields
+           .iter()
+           .any(|field| field.attrs.flatten() || field.attrs.serialize_names_by_readability().is_some()) {
+        Some(quote! {
+            let __serde_human_readable = _serde::Serializer::is_human_readable(&__serializer);
+        })
+    } else {
+        None
+    }
+}
+
+fn gate_var(field: &Field) -> Ident {
+    let ident = field.ident.as_ref().expect("checked in serde_derive_internals");
+    Ident::new(format!("__serde_include_{}", ident))
+}
+
+// The key under which a struct field is serialized. Either the string literal
+// of its serialized name, the path given by `#[serde(rename_const = "...")]`,
+// or a choice between `rename_hr` and `rename_compact` evaluated against the
+// local set up by `serializer_gates`.
+fn field_key(field: &Field) -> Tokens {
+    if let Some((hr, compact)) = field.attrs.serialize_names_by_readability() {
+        return quote!((if __serde_human_readable { #hr } else { #compact }));
+    }
+
+    match field.attrs.serialize_name_const() {
+        Some(path) => quote!(#path),
+        None => {
+            let name = field.attrs.name().serialize_name();
+            quote!(#name)
+        }
+    }
+}
+
+fn transform_key(key: Tokens, key_transform: Option<&syn::Path>) -> Tokens {
+    match key_transform {
+        Some(path) => quote!(&#path(#key)),
+        None => key,
+    }
+}
+
+fn wrap_serialize_field(params: &Parameters, field: &Field, value: Tokens) -> Tokens {
+    if let Some(path) = field.attrs.serialize_with() {
+        wrap_serialize_with(params, field.ty, path, value)
+    } else if let Some(precision) = field.attrs.float_precision() {
+        quote!(&_serde::private::ser::FloatPrecision(*#value, #precision))
+    } else if field.attrs.int128_as_string() {
+        quote!(&_serde::private::ser::DisplayAsString(#value))
+    } else if field.attrs.char_as_str() {
+        quote!(&_serde::private::ser::CharAsStr(*#value))
+    } else if field.attrs.raw() {
+        quote!(&_serde::private::ser::RawValue(#value))
+    } else if field.attrs.serialize_bytes() {
+        quote!(&_serde::private::ser::Bytes(#value))
+    } else if let Some(sentinel) = field.attrs.null_sentinel() {
+        quote!(&_serde::private::ser::OrSentinel(#value, #(#sentinel)*))
+    } else if let (Some(non_finite), true) =
+        (params.non_finite, field.is_primitive(&["f32", "f64"])) {
+        // Floats are recognized by name, so type aliases of `f32` and `f64`
+        // are serialized as usual.
+        let mode = match non_finite {
+            attr::NonFinite::Null => quote!(Null),
+            attr::NonFinite::Error => quote!(Error),
+            attr::NonFinite::String => quote!(String),
+        };
+        quote!(&_serde::private::ser::NonFinite(*#value, _serde::private::ser::NonFiniteMode::#mode))
+    } else {
+        value
+    }
+}
+
 fn wrap_serialize_with(
     params: &Parameters,
     field_ty: &syn::Ty,
@@ -59,7 +59,9 @@ impl<'a> Container<'a> {
         match body {
             Body::Enum(ref mut variants) => {
                 for ref mut variant in variants {
-                    variant.attrs.rename_by_rule(attrs.rename_all());
+                    if !is_rename_skipped(&attrs, &variant.ident) {
+                        variant.attrs.rename_by_rule(attrs.rename_all());
+                    }
                     for ref mut field in &mut variant.fields {
                         field.attrs.rename_by_rule(variant.attrs.rename_all());
                     }
@@ -67,7 +69,13 @@ impl<'a> Container<'a> {
             }
             Body::Struct(_, ref mut fields) => {
                 for field in fields {
-                    field.attrs.rename_by_rule(attrs.rename_all());
+                    let skipped = field
+                        .ident
+                        .as_ref()
+                        .map_or(false, |ident| is_rename_skipped(&attrs, ident));
+                    if !skipped {
+                        field.attrs.rename_by_rule(attrs.rename_all());
+                    }
                 }
             }
         }
@@ -98,6 +106,50 @@ impl<'a> Body<'a> {
     }
 }
 
+impl<'a> Variant<'a> {
+    /// The style the variant is serialized and deserialized as. A tuple or
+    /// struct variant whose fields are all skipped in both directions is
+    /// represented as if it were a unit variant. A variant whose fields are
+    /// only skipped in one direction keeps its style, so that it round trips.
+    pub fn represented_style(&self) -> Style {
+        let skipped =
+            |field: &Field| field.attrs.skip_serializing() && field.attrs.skip_deserializing();
+        match self.style {
+            Style::Tuple | Style::Struct
+                if !self.fields.is_empty() && self.fields.iter().all(skipped) => Style::Unit,
+            style => style,
+        }
+    }
+}
+
+impl<'a> Field<'a> {
+    /// Whether the type of the field is spelled as an `Option`. Type aliases
+    /// are not recognized.
+    pub fn is_option(&self) -> bool {
+        match *self.ty {
+            syn::Ty::Path(None, ref path) => {
+                path.segments.last().map_or(false, |seg| seg.ident == "Option")
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the type of the field is spelled as one of the given primitive
+    /// types. Type aliases are not recognized.
+    pub fn is_primitive(&self, names: &[&str]) -> bool {
+        match *self.ty {
+            syn::Ty::Path(None, ref path) if path.segments.len() == 1 => {
+                names.iter().any(|name| path.segments[0].ident == *name)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn is_rename_skipped(attrs: &attr::Container, ident: &syn::Ident) -> bool {
+    attrs.rename_all_skip().iter().any(|name| ident == name)
+}
+
 fn enum_from_ast<'a>(cx: &Ctxt, variants: &'a [syn::Variant]) -> Vec<Variant<'a>> {
     variants
         .iter()
@@ -113,6 +113,46 @@ pub struct Container {
     into_type: Option<syn::Ty>,
     remote: Option<syn::Path>,
     identifier: Identifier,
+    partial: bool,
+    as_flags: bool,
+    intern_keys: bool,
+    computed: Vec<Computed>,
+    tuple_names: Option<Vec<String>>,
+    emit_field_info: bool,
+    scalar_field: Option<String>,
+    external_as_map: bool,
+    transparent: bool,
+    with_serializer: Option<syn::Path>,
+    key_transform: Option<syn::Path>,
+    struct_tag: Option<(String, Option<String>)>,
+    as_empty_map: bool,
+    newtype_option: Option<NewtypeOption>,
+    skip_if_all_fields_skipped: bool,
+    non_finite: Option<NonFinite>,
+    rename_all_skip: Vec<String>,
+    unit_as_null: bool,
+    single_variant: Option<SingleVariant>,
+    newtype_name: Option<String>,
+    field_order: Option<FieldOrder>,
+}
+
+/// A synthetic field declared by `#[serde(computed(name = "...", value = "..."))]`.
+#[derive(Debug)]
+pub struct Computed {
+    name: String,
+    value: syn::Path,
+}
+
+impl Computed {
+    /// Return the key under which the computed value is serialized.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Return the path of the function computing the value from `&Self`.
+    pub fn value(&self) -> &syn::Path {
+        &self.value
+    }
 }
 
 /// Styles of representing an enum.
@@ -164,6 +204,65 @@ pub enum Identifier {
     Variant,
 }
 
+/// Order in which named fields are serialized, from
+/// `#[serde(field_order = "...")]`. Without it fields are serialized in
+/// declaration order.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum FieldOrder {
+    /// Shortest serialized name first. Names of the same length are ordered
+    /// by comparing their UTF-8 bytes.
+    CanonicalLength,
+}
+
+/// The predicate of `#[serde(skip_serializing_if = "...")]`.
+#[derive(Debug)]
+pub enum SkipPredicate {
+    /// Path to a function taking a reference to the field.
+    Path(syn::Path),
+
+    /// Closure literal taking a reference to the field, as tokens.
+    Closure(Vec<syn::TokenTree>),
+}
+
+/// How the only variant of an enum is represented, from
+/// `#[serde(single_variant = "...")]`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SingleVariant {
+    /// Represent the variant like any variant of a multi-variant enum. This
+    /// is the default.
+    Tagged,
+
+    /// Represent the variant without its name, as `#[serde(transparent)]`.
+    Transparent,
+}
+
+/// How a newtype struct around an `Option` is serialized, from
+/// `#[serde(newtype_option = "...")]`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum NewtypeOption {
+    /// Serialize the `Option` directly, as if there were no newtype.
+    Inline,
+
+    /// Wrap the `Option` with `serialize_newtype_struct`. This is how any
+    /// other newtype struct is serialized.
+    Wrap,
+}
+
+/// How `f32` and `f64` fields holding NaN or an infinity are serialized, from
+/// `#[serde(non_finite = "...")]`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum NonFinite {
+    /// Serialize as `None`.
+    Null,
+
+    /// Fail with a serialization error.
+    Error,
+
+    /// Serialize as one of the strings `"NaN"`, `"Infinity"` or
+    /// `"-Infinity"`.
+    String,
+}
+
 impl Container {
     /// Extract out the `#[serde(...)]` attributes from an item.
     pub fn from_ast(cx: &Ctxt, item: &syn::DeriveInput) -> Self {
@@ -182,6 +281,28 @@ impl Container {
         let mut remote = Attr::none(cx, "remote");
         let mut field_identifier = BoolAttr::none(cx, "field_identifier");
         let mut variant_identifier = BoolAttr::none(cx, "variant_identifier");
+        let mut partial = BoolAttr::none(cx, "partial");
+        let mut as_flags = BoolAttr::none(cx, "as_flags");
+        let mut intern_keys = BoolAttr::none(cx, "intern_keys");
+        let mut computed = Vec::new();
+        let mut tuple_names = Attr::none(cx, "tuple_names");
+        let mut emit_field_info = BoolAttr::none(cx, "emit_field_info");
+        let mut scalar_field = Attr::none(cx, "scalar_field");
+        let mut external_as_map = BoolAttr::none(cx, "external_as_map");
+        let mut transparent = BoolAttr::none(cx, "transparent");
+        let mut with_serializer = Attr::none(cx, "with_serializer");
+        let mut key_transform = Attr::none(cx, "key_transform");
+        let mut struct_tag = Attr::none(cx, "tag");
+        let mut tag_value = Attr::none(cx, "tag_value");
+        let mut as_empty_map = BoolAttr::none(cx, "as_empty_map");
+        let mut newtype_option = Attr::none(cx, "newtype_option");
+        let mut single_variant = Attr::none(cx, "single_variant");
+        let mut newtype_name = Attr::none(cx, "newtype_name");
+        let mut field_order = Attr::none(cx, "field_order");
+        let mut skip_if_all_fields_skipped = BoolAttr::none(cx, "skip_if_all_fields_skipped");
+        let mut non_finite = Attr::none(cx, "non_finite");
+        let mut rename_all_skip = Attr::none(cx, "rename_all_skip");
+        let mut unit_as_null = BoolAttr::none(cx, "unit_as_null");
 
         for meta_items in item.attrs.iter().filter_map(get_serde_meta_items) {
             for meta_item in meta_items {
@@ -289,8 +410,31 @@ impl Container {
                                 syn::Body::Enum(_) => {
                                     internal_tag.set(s);
                                 }
+                                syn::Body::Struct(syn::VariantData::Struct(_)) => {
+                                    struct_tag.set(s);
+                                }
                                 syn::Body::Struct(_) => {
-                                    cx.error("#[serde(tag = \"...\")] can only be used on enums")
+                                    cx.error(
+                                        "#[serde(tag = \"...\")] can only be used on enums \
+                                              and structs with named fields",
+                                    )
+                                }
+                            }
+                        }
+                    }
+
+                    // Parse `#[serde(tag_value = "Widget")]`
+                    MetaItem(NameValue(ref name, ref lit)) if name == "tag_value" => {
+                        if let Ok(s) = get_string_from_lit(cx, name.as_ref(), name.as_ref(), lit) {
+                            match item.body {
+                                syn::Body::Struct(syn::VariantData::Struct(_)) => {
+                                    tag_value.set(s);
+                                }
+                                _ => {
+                                    cx.error(
+                                        "#[serde(tag_value = \"...\")] can only be used on \
+                                              structs with named fields",
+                                    )
                                 }
                             }
                         }
@@ -327,6 +471,13 @@ impl Container {
                         }
                     }
 
+                    // Parse `#[serde(with_serializer = "...")]`
+                    MetaItem(NameValue(ref name, ref lit)) if name == "with_serializer" => {
+                        if let Ok(path) = parse_lit_into_path(cx, name.as_ref(), lit) {
+                            with_serializer.set(path);
+                        }
+                    }
+
                     // Parse `#[serde(remote = "...")]`
                     MetaItem(NameValue(ref name, ref lit)) if name == "remote" => {
                         if let Ok(path) = parse_lit_into_path(cx, name.as_ref(), lit) {
@@ -344,6 +495,358 @@ impl Container {
                         variant_identifier.set_true();
                     }
 
+                    // Parse `#[serde(partial)]`
+                    MetaItem(Word(ref name)) if name == "partial" => {
+                        match item.body {
+                            syn::Body::Struct(syn::VariantData::Struct(_)) => {
+                                partial.set_true();
+                            }
+                            _ => {
+                                cx.error(
+                                    "#[serde(partial)] can only be used on structs \
+                                          with named fields",
+                                )
+                            }
+                        }
+                    }
+
+                    // Parse `#[serde(as_flags)]`
+                    MetaItem(Word(ref name)) if name == "as_flags" => {
+                        match item.body {
+                            syn::Body::Struct(syn::VariantData::Struct(_)) => {
+                                as_flags.set_true();
+                            }
+                            _ => {
+                                cx.error(
+                                    "#[serde(as_flags)] can only be used on structs \
+                                          with named fields",
+                                )
+                            }
+                        }
+                    }
+
+                    // Parse `#[serde(intern_keys)]`
+                    MetaItem(Word(ref name)) if name == "intern_keys" => {
+                        match item.body {
+                            syn::Body::Struct(syn::VariantData::Struct(_)) => {
+                                intern_keys.set_true();
+                            }
+                            _ => {
+                                cx.error(
+                                    "#[serde(intern_keys)] can only be used on structs \
+                                          with named fields",
+                                )
+                            }
+                        }
+                    }
+
+                    // Parse `#[serde(key_transform = "...")]`
+                    MetaItem(NameValue(ref name, ref lit)) if name == "key_transform" => {
+                        if let Ok(path) = parse_lit_into_path(cx, name.as_ref(), lit) {
+                            match item.body {
+                                syn::Body::Struct(syn::VariantData::Struct(_)) => {
+                                    key_transform.set(path);
+                                }
+                                _ => {
+                                    cx.error(
+                                        "#[serde(key_transform = \"...\")] can only be used on \
+                                              structs with named fields",
+                                    )
+                                }
+                            }
+                        }
+                    }
+
+                    // Parse `#[serde(computed(name = "full", value = "Self::full"))]`
+                    MetaItem(List(ref name, ref meta_items)) if name == "computed" => {
+                        if let Ok(c) = get_computed(cx, meta_items) {
+                            match item.body {
+                                syn::Body::Struct(syn::VariantData::Struct(_)) => {
+                                    computed.push(c);
+                                }
+                                _ => {
+                                    cx.error(
+                                        "#[serde(computed(...))] can only be used on structs \
+                                              with named fields",
+                                    )
+                                }
+                            }
+                        }
+                    }
+
+                    // Parse `#[serde(non_finite = "null")]`
+                    MetaItem(NameValue(ref name, ref lit)) if name == "non_finite" => {
+                        if let Ok(s) = get_string_from_lit(cx, name.as_ref(), name.as_ref(), lit) {
+                            match s.as_str() {
+                                "null" => non_finite.set(NonFinite::Null),
+                                "error" => non_finite.set(NonFinite::Error),
+                                "string" => non_finite.set(NonFinite::String),
+                                _ => {
+                                    cx.error(
+                                        format!(
+                                            "unknown value for #[serde(non_finite = {:?})]",
+                                            s
+                                        ),
+                                    )
+                                }
+                            }
+                        }
+                    }
+
+                    // Parse `#[serde(field_order = "canonical_length")]`
+                    MetaItem(NameValue(ref name, ref lit)) if name == "field_order" => {
+                        if let Ok(s) = get_string_from_lit(cx, name.as_ref(), name.as_ref(), lit) {
+                            match s.as_str() {
+                                "canonical_length" => field_order.set(FieldOrder::CanonicalLength),
+                                _ => {
+                                    cx.error(
+                                        format!(
+                                            "unknown value for #[serde(field_order = {:?})]",
+                                            s
+                                        ),
+                                    )
+                                }
+                            }
+                        }
+                    }
+
+                    // Parse `#[serde(skip_if_all_fields_skipped)]`
+                    MetaItem(Word(ref name)) if name == "skip_if_all_fields_skipped" => {
+                        match item.body {
+                            syn::Body::Struct(syn::VariantData::Struct(_)) => {
+                                skip_if_all_fields_skipped.set_true();
+                            }
+                            _ => {
+                                cx.error(
+                                    "#[serde(skip_if_all_fields_skipped)] can only be used on \
+                                     structs with named fields",
+                                )
+                            }
+                        }
+                    }
+
+                    // Parse `#[serde(emit_field_info)]`
+                    MetaItem(Word(ref name)) if name == "emit_field_info" => {
+                        match item.body {
+                            syn::Body::Struct(syn::VariantData::Struct(_)) => {
+                                emit_field_info.set_true();
+                            }
+                            _ => {
+                                cx.error(
+                                    "#[serde(emit_field_info)] can only be used on structs \
+                                          with named fields",
+                                )
+                            }
+                        }
+                    }
+
+                    // Parse `#[serde(rename_all_skip = "id, url")]`
+                    MetaItem(NameValue(ref name, ref lit)) if name == "rename_all_skip" => {
+                        if let Ok(s) = get_string_from_lit(cx, name.as_ref(), name.as_ref(), lit) {
+                            let names: Vec<_> = s.split(',').map(|n| n.trim().to_owned()).collect();
+                            let idents: Vec<_> = match item.body {
+                                syn::Body::Struct(syn::VariantData::Struct(ref fields)) => {
+                                    fields.iter().filter_map(|f| f.ident.as_ref()).collect()
+                                }
+                                syn::Body::Enum(ref variants) => {
+                                    variants.iter().map(|v| &v.ident).collect()
+                                }
+                                syn::Body::Struct(_) => {
+                                    cx.error(
+                                        "#[serde(rename_all_skip)] can only be used on enums and \
+                                         structs with named fields",
+                                    );
+                                    continue;
+                                }
+                            };
+                            match names.iter().find(|n| !idents.iter().any(|i| i == n)) {
+                                Some(unknown) => {
+                                    cx.error(
+                                        format!(
+                                            "#[serde(rename_all_skip)] refers to unknown field or \
+                                             variant `{}`",
+                                            unknown
+                                        ),
+                                    )
+                                }
+                                None => rename_all_skip.set(names),
+                            }
+                        }
+                    }
+
+                    // Parse `#[serde(tuple_names = "x, y, z")]`
+                    MetaItem(NameValue(ref name, ref lit)) if name == "tuple_names" => {
+                        if let Ok(s) = get_string_from_lit(cx, name.as_ref(), name.as_ref(), lit) {
+                            let names: Vec<_> = s.split(',').map(|n| n.trim().to_owned()).collect();
+                            match item.body {
+                                syn::Body::Struct(syn::VariantData::Tuple(ref fields)) => {
+                                    if names.iter().any(String::is_empty) {
+                                        cx.error(format!("#[serde(tuple_names)] contains an empty name: {:?}", s));
+                                    } else if names.len() != fields.len() {
+                                        cx.error(
+                                            format!(
+                                                "#[serde(tuple_names)] has {} names but the struct has {} fields",
+                                                names.len(),
+                                                fields.len()
+                                            ),
+                                        );
+                                    } else {
+                                        tuple_names.set(names);
+                                    }
+                                }
+                                _ => cx.error("#[serde(tuple_names)] can only be used on tuple structs"),
+                            }
+                        }
+                    }
+
+                    // Parse `#[serde(external_as_map)]`
+                    MetaItem(Word(ref name)) if name == "external_as_map" => {
+                        match item.body {
+                            syn::Body::Enum(_) => {
+                                external_as_map.set_true();
+                            }
+                            syn::Body::Struct(_) => {
+                                cx.error("#[serde(external_as_map)] can only be used on enums")
+                            }
+                        }
+                    }
+
+                    // Parse `#[serde(as_empty_map)]`
+                    MetaItem(Word(ref name)) if name == "as_empty_map" => {
+                        match item.body {
+                            syn::Body::Struct(syn::VariantData::Unit) => {
+                                as_empty_map.set_true();
+                            }
+                            _ => {
+                                cx.error("#[serde(as_empty_map)] can only be used on unit structs")
+                            }
+                        }
+                    }
+
+                    // Parse `#[serde(unit_as_null)]`
+                    MetaItem(Word(ref name)) if name == "unit_as_null" => {
+                        match item.body {
+                            syn::Body::Struct(syn::VariantData::Unit) => {
+                                unit_as_null.set_true();
+                            }
+                            _ => {
+                                cx.error(
+                                    "#[serde(unit_as_null)] can only be used on unit structs \
+                                     and unit variants",
+                                )
+                            }
+                        }
+                    }
+
+                    // Parse `#[serde(newtype_option = "inline")]`
+                    MetaItem(NameValue(ref name, ref lit)) if name == "newtype_option" => {
+                        if let Ok(s) = get_string_from_lit(cx, name.as_ref(), name.as_ref(), lit) {
+                            match item.body {
+                                syn::Body::Struct(syn::VariantData::Tuple(ref fields))
+                                    if fields.len() == 1 => {
+                                    match s.as_str() {
+                                        "inline" => newtype_option.set(NewtypeOption::Inline),
+                                        "wrap" => newtype_option.set(NewtypeOption::Wrap),
+                                        _ => {
+                                            cx.error(
+                                                format!(
+                                                    "unknown value for \
+                                                     #[serde(newtype_option = {:?})]",
+                                                    s
+                                                ),
+                                            )
+                                        }
+                                    }
+                                }
+                                _ => {
+                                    cx.error(
+                                        "#[serde(newtype_option = \"...\")] can only be used on \
+                                         newtype structs",
+                                    )
+                                }
+                            }
+                        }
+                    }
+
+                    // Parse `#[serde(newtype_name = "none")]`
+                    MetaItem(NameValue(ref name, ref lit)) if name == "newtype_name" => {
+                        if let Ok(s) = get_string_from_lit(cx, name.as_ref(), name.as_ref(), lit) {
+                            match item.body {
+                                syn::Body::Struct(syn::VariantData::Tuple(ref fields))
+                                    if fields.len() == 1 => {
+                                    if s == "none" {
+                                        newtype_name.set(String::new());
+                                    } else {
+                                        newtype_name.set(s);
+                                    }
+                                }
+                                _ => {
+                                    cx.error(
+                                        "#[serde(newtype_name = \"...\")] can only be used on \
+                                         newtype structs",
+                                    )
+                                }
+                            }
+                        }
+                    }
+
+                    // Parse `#[serde(single_variant = "transparent")]`
+                    MetaItem(NameValue(ref name, ref lit)) if name == "single_variant" => {
+                        if let Ok(s) = get_string_from_lit(cx, name.as_ref(), name.as_ref(), lit) {
+                            match item.body {
+                                syn::Body::Enum(_) => {
+                                    match s.as_str() {
+                                        "tagged" => single_variant.set(SingleVariant::Tagged),
+                                        "transparent" => {
+                                            single_variant.set(SingleVariant::Transparent)
+                                        }
+                                        _ => {
+                                            cx.error(
+                                                format!(
+                                                    "unknown value for \
+                                                     #[serde(single_variant = {:?})]",
+                                                    s
+                                                ),
+                                            )
+                                        }
+                                    }
+                                }
+                                syn::Body::Struct(_) => {
+                                    cx.error(
+                                        "#[serde(single_variant = \"...\")] can only be used on \
+                                         enums",
+                                    )
+                                }
+                            }
+                        }
+                    }
+
+                    // Parse `#[serde(transparent)]`
+                    MetaItem(Word(ref name)) if name == "transparent" => {
+                        transparent.set_true();
+                    }
+
+                    // Parse `#[serde(scalar_field = "id")]`
+                    MetaItem(NameValue(ref name, ref lit)) if name == "scalar_field" => {
+                        if let Ok(s) = get_string_from_lit(cx, name.as_ref(), name.as_ref(), lit) {
+                            match item.body {
+                                syn::Body::Struct(syn::VariantData::Struct(ref fields)) => {
+                                    if fields.iter().any(|field| field.ident.as_ref().map_or(false, |ident| *ident == s)) {
+                                        scalar_field.set(s);
+                                    } else {
+                                        cx.error(format!("#[serde(scalar_field)] names unknown field `{}`", s));
+                                    }
+                                }
+                                _ => {
+                                    cx.error(
+                                        "#[serde(scalar_field)] can only be used on structs \
+                                              with named fields",
+                                    )
+                                }
+                            }
+                        }
+                    }
+
                     MetaItem(ref meta_item) => {
                         cx.error(format!("unknown serde container attribute `{}`",
                                          meta_item.name()));
@@ -356,6 +859,15 @@ impl Container {
             }
         }
 
+        let struct_tag = match (struct_tag.get(), tag_value.get()) {
+            (Some(tag), value) => Some((tag, value)),
+            (None, Some(_)) => {
+                cx.error("#[serde(tag_value = \"...\")] requires #[serde(tag = \"...\")]");
+                None
+            }
+            (None, None) => None,
+        };
+
         Container {
             name: Name {
                 serialize: ser_name.get().unwrap_or_else(|| item.ident.to_string()),
@@ -371,6 +883,27 @@ impl Container {
             into_type: into_type.get(),
             remote: remote.get(),
             identifier: decide_identifier(cx, item, field_identifier, variant_identifier),
+            partial: partial.get(),
+            as_flags: as_flags.get(),
+            intern_keys: intern_keys.get(),
+            computed: computed,
+            tuple_names: tuple_names.get(),
+            emit_field_info: emit_field_info.get(),
+            scalar_field: scalar_field.get(),
+            external_as_map: external_as_map.get(),
+            transparent: transparent.get(),
+            with_serializer: with_serializer.get(),
+            key_transform: key_transform.get(),
+            struct_tag: struct_tag,
+            as_empty_map: as_empty_map.get(),
+            newtype_option: newtype_option.get(),
+            skip_if_all_fields_skipped: skip_if_all_fields_skipped.get(),
+            non_finite: non_finite.get(),
+            rename_all_skip: rename_all_skip.get().unwrap_or_else(Vec::new),
+            unit_as_null: unit_as_null.get(),
+            single_variant: single_variant.get(),
+            newtype_name: newtype_name.get(),
+            field_order: field_order.get(),
         }
     }
 
@@ -382,6 +915,12 @@ impl Container {
         &self.rename_all
     }
 
+    /// Fields or variants, by their Rust name, that keep their name instead of
+    /// following `rename_all`.
+    pub fn rename_all_skip(&self) -> &[String] {
+        &self.rename_all_skip
+    }
+
     pub fn deny_unknown_fields(&self) -> bool {
         self.deny_unknown_fields
     }
@@ -417,6 +956,122 @@ impl Container {
     pub fn identifier(&self) -> Identifier {
         self.identifier
     }
+
+    pub fn partial(&self) -> bool {
+        self.partial
+    }
+
+    pub fn as_flags(&self) -> bool {
+        self.as_flags
+    }
+
+    pub fn intern_keys(&self) -> bool {
+        self.intern_keys
+    }
+
+    pub fn computed(&self) -> &[Computed] {
+        &self.computed
+    }
+
+    pub fn tuple_names(&self) -> Option<&[String]> {
+        self.tuple_names.as_ref().map(|vec| &vec[..])
+    }
+
+    pub fn emit_field_info(&self) -> bool {
+        self.emit_field_info
+    }
+
+    /// Whether to generate an inherent `__serde_is_empty` method that tells if
+    /// every field would be skipped, for use as the `skip_serializing_if`
+    /// predicate of a parent field.
+    pub fn skip_if_all_fields_skipped(&self) -> bool {
+        self.skip_if_all_fields_skipped
+    }
+
+    /// How `f32` and `f64` fields without another serialization override
+    /// handle NaN and infinities.
+    pub fn non_finite(&self) -> Option<NonFinite> {
+        self.non_finite
+    }
+
+    /// Order of named fields in the serialized struct or struct variant,
+    /// resolved at compile time from their serialized names.
+    pub fn field_order(&self) -> Option<FieldOrder> {
+        self.field_order
+    }
+
+    /// Name of the field the struct is serialized as, from
+    /// `#[serde(scalar_field = "...")]`. All other fields are left out of the
+    /// output.
+    pub fn scalar_field(&self) -> Option<&str> {
+        self.scalar_field.as_ref().map(|s| &s[..])
+    }
+
+    /// Every variant, including unit variants, is serialized as a map with
+    /// the variant name as its single key.
+    pub fn external_as_map(&self) -> bool {
+        self.external_as_map
+    }
+
+    /// The type is represented exactly like its only field, or like the
+    /// payload of its only variant.
+    pub fn transparent(&self) -> bool {
+        self.transparent || self.single_variant == Some(SingleVariant::Transparent)
+    }
+
+    pub fn single_variant(&self) -> Option<SingleVariant> {
+        self.single_variant
+    }
+
+    /// The unit struct is serialized as an empty map rather than as a unit
+    /// struct.
+    pub fn as_empty_map(&self) -> bool {
+        self.as_empty_map
+    }
+
+    pub fn newtype_option(&self) -> Option<NewtypeOption> {
+        self.newtype_option
+    }
+
+    /// The name passed to `serialize_newtype_struct` in place of the
+    /// serialized name of the container. `#[serde(newtype_name = "none")]`
+    /// passes an empty name.
+    pub fn newtype_name(&self) -> Option<&str> {
+        self.newtype_name.as_ref().map(|s| &s[..])
+    }
+
+    /// The unit struct is serialized with `serialize_none`.
+    pub fn unit_as_null(&self) -> bool {
+        self.unit_as_null
+    }
+
+    /// Path to a function that is given the serializer before anything is
+    /// serialized and returns the serializer to use instead. It has the
+    /// signature `fn<S: Serializer>(S) -> A` where `A` is a `Serializer` with
+    /// the same `Ok` and `Error` types as `S`.
+    pub fn with_serializer(&self) -> Option<&syn::Path> {
+        self.with_serializer.as_ref()
+    }
+
+    /// Path to a `fn(&str) -> Cow<str>` that every key of the struct is
+    /// passed through at runtime before it is serialized.
+    pub fn key_transform(&self) -> Option<&syn::Path> {
+        self.key_transform.as_ref()
+    }
+
+    /// Key and value of the constant field that `#[serde(tag = "...")]` adds
+    /// in front of the fields of a struct. The value is given by
+    /// `#[serde(tag_value = "...")]` and defaults to the struct's name.
+    pub fn struct_tag(&self) -> Option<(&str, &str)> {
+        self.struct_tag
+            .as_ref()
+            .map(
+                |&(ref tag, ref value)| {
+                    let value = value.as_ref().unwrap_or(&self.name.serialize);
+                    (&tag[..], &value[..])
+                },
+            )
+    }
 }
 
 fn decide_tag(
@@ -510,6 +1165,10 @@ pub struct Variant {
     skip_deserializing: bool,
     skip_serializing: bool,
     other: bool,
+    flatten: bool,
+    as_seq: bool,
+    tag: Option<String>,
+    unit_as_null: bool,
 }
 
 impl Variant {
@@ -520,6 +1179,10 @@ impl Variant {
         let mut skip_serializing = BoolAttr::none(cx, "skip_serializing");
         let mut rename_all = Attr::none(cx, "rename_all");
         let mut other = BoolAttr::none(cx, "other");
+        let mut flatten = BoolAttr::none(cx, "flatten");
+        let mut as_seq = BoolAttr::none(cx, "as_seq");
+        let mut tag = Attr::none(cx, "tag");
+        let mut unit_as_null = BoolAttr::none(cx, "unit_as_null");
 
         for meta_items in variant.attrs.iter().filter_map(get_serde_meta_items) {
             for meta_item in meta_items {
@@ -569,6 +1232,53 @@ impl Variant {
                         other.set_true();
                     }
 
+                    // Parse `#[serde(flatten)]`
+                    MetaItem(Word(ref name)) if name == "flatten" => {
+                        flatten.set_true();
+                    }
+
+                    // Parse `#[serde(as_seq)]`
+                    MetaItem(Word(ref name)) if name == "as_seq" => {
+                        match variant.data {
+                            syn::VariantData::Struct(_) => {
+                                as_seq.set_true();
+                            }
+                            _ => {
+                                cx.error("#[serde(as_seq)] can only be used on struct variants");
+                            }
+                        }
+                    }
+
+                    // Parse `#[serde(unit_as_null)]`
+                    MetaItem(Word(ref name)) if name == "unit_as_null" => {
+                        match variant.data {
+                            syn::VariantData::Unit => {
+                                unit_as_null.set_true();
+                            }
+                            _ => {
+                                cx.error(
+                                    "#[serde(unit_as_null)] can only be used on unit structs \
+                                     and unit variants",
+                                )
+                            }
+                        }
+                    }
+
+                    // Parse `#[serde(tag = "type")]`
+                    MetaItem(NameValue(ref name, ref lit)) if name == "tag" => {
+                        if let Ok(s) = get_string_from_lit(cx, name.as_ref(), name.as_ref(), lit) {
+                            match variant.data {
+                                syn::VariantData::Tuple(ref fields) if fields.len() != 1 => {
+                                    cx.error(
+                                        "#[serde(tag = \"...\")] cannot be used on tuple \
+                                         variants",
+                                    );
+                                }
+                                _ => tag.set(s),
+                            }
+                        }
+                    }
+
                     MetaItem(ref meta_item) => {
                         cx.error(format!("unknown serde variant attribute `{}`", meta_item.name()));
                     }
@@ -595,6 +1305,10 @@ impl Variant {
             skip_deserializing: skip_deserializing.get(),
             skip_serializing: skip_serializing.get(),
             other: other.get(),
+            flatten: flatten.get(),
+            as_seq: as_seq.get(),
+            tag: tag.get(),
+            unit_as_null: unit_as_null.get(),
         }
     }
 
@@ -626,17 +1340,42 @@ impl Variant {
     pub fn other(&self) -> bool {
         self.other
     }
+
+    pub fn flatten(&self) -> bool {
+        self.flatten
+    }
+
+    pub fn as_seq(&self) -> bool {
+        self.as_seq
+    }
+
+    /// Tag of a variant that is internally tagged even though the rest of
+    /// the enum is externally tagged.
+    pub fn tag(&self) -> Option<&str> {
+        self.tag.as_ref().map(|s| &s[..])
+    }
+
+    /// The unit variant is serialized with `serialize_none`, without its
+    /// name.
+    pub fn unit_as_null(&self) -> bool {
+        self.unit_as_null
+    }
 }
 
 /// Represents field attribute information
 #[derive(Debug)]
 pub struct Field {
     name: Name,
+    de_aliases: Vec<String>,
+    ser_name_const: Option<syn::Path>,
+    ser_name_hr: Option<String>,
+    ser_name_compact: Option<String>,
     ser_renamed: bool,
     de_renamed: bool,
     skip_serializing: bool,
     skip_deserializing: bool,
-    skip_serializing_if: Option<syn::Path>,
+    skip_serializing_if: Option<SkipPredicate>,
+    skip_serializing_unless_serializer: Option<syn::Path>,
     default: Default,
     serialize_with: Option<syn::Path>,
     deserialize_with: Option<syn::Path>,
@@ -644,6 +1383,14 @@ pub struct Field {
     de_bound: Option<Vec<syn::WherePredicate>>,
     borrowed_lifetimes: BTreeSet<syn::Lifetime>,
     getter: Option<syn::Path>,
+    flatten: bool,
+    float_precision: Option<usize>,
+    int128_as_string: bool,
+    char_as_str: bool,
+    raw: bool,
+    serialize_bytes: bool,
+    description: Option<String>,
+    null_sentinel: Option<Vec<syn::TokenTree>>,
 }
 
 /// Represents the default to use for a field when deserializing.
@@ -662,9 +1409,15 @@ impl Field {
     pub fn from_ast(cx: &Ctxt, index: usize, field: &syn::Field) -> Self {
         let mut ser_name = Attr::none(cx, "rename");
         let mut de_name = Attr::none(cx, "rename");
+        let mut de_aliases = Vec::new();
+        let mut ser_name_const = Attr::none(cx, "rename_const");
+        let mut ser_name_hr = Attr::none(cx, "rename_hr");
+        let mut ser_name_compact = Attr::none(cx, "rename_compact");
         let mut skip_serializing = BoolAttr::none(cx, "skip_serializing");
         let mut skip_deserializing = BoolAttr::none(cx, "skip_deserializing");
         let mut skip_serializing_if = Attr::none(cx, "skip_serializing_if");
+        let mut skip_serializing_unless_serializer =
+            Attr::none(cx, "skip_serializing_unless_serializer");
         let mut default = Attr::none(cx, "default");
         let mut serialize_with = Attr::none(cx, "serialize_with");
         let mut deserialize_with = Attr::none(cx, "deserialize_with");
@@ -672,6 +1425,14 @@ impl Field {
         let mut de_bound = Attr::none(cx, "bound");
         let mut borrowed_lifetimes = Attr::none(cx, "borrow");
         let mut getter = Attr::none(cx, "getter");
+        let mut flatten = BoolAttr::none(cx, "flatten");
+        let mut float_precision = Attr::none(cx, "float_precision");
+        let mut int128_as_string = BoolAttr::none(cx, "int128_as_string");
+        let mut char_as_str = BoolAttr::none(cx, "char_as_str");
+        let mut raw = BoolAttr::none(cx, "raw");
+        let mut serialize_bytes = BoolAttr::none(cx, "serialize_bytes");
+        let mut description = Attr::none(cx, "description");
+        let mut null_sentinel = Attr::none(cx, "null_sentinel");
 
         let ident = match field.ident {
             Some(ref ident) => ident.to_string(),
@@ -689,6 +1450,19 @@ impl Field {
                         }
                     }
 
+                    // Parse `#[serde(rename("new_name", "old_name"))]`
+                    MetaItem(List(ref name, ref meta_items))
+                        if name == "rename" && is_literal_list(meta_items) => {
+                        if let Ok(names) = get_rename_chain(cx, meta_items) {
+                            let mut names = names.into_iter();
+                            if let Some(first) = names.next() {
+                                ser_name.set(first.clone());
+                                de_name.set(first);
+                            }
+                            de_aliases = names.collect();
+                        }
+                    }
+
                     // Parse `#[serde(rename(serialize = "foo", deserialize = "bar"))]`
                     MetaItem(List(ref name, ref meta_items)) if name == "rename" => {
                         if let Ok((ser, de)) = get_renames(cx, meta_items) {
@@ -697,6 +1471,27 @@ impl Field {
                         }
                     }
 
+                    // Parse `#[serde(rename_const = "path::TO::NAME")]`
+                    MetaItem(NameValue(ref name, ref lit)) if name == "rename_const" => {
+                        if let Ok(path) = parse_lit_into_path(cx, name.as_ref(), lit) {
+                            ser_name_const.set(path);
+                        }
+                    }
+
+                    // Parse `#[serde(rename_hr = "foo")]`
+                    MetaItem(NameValue(ref name, ref lit)) if name == "rename_hr" => {
+                        if let Ok(s) = get_string_from_lit(cx, name.as_ref(), name.as_ref(), lit) {
+                            ser_name_hr.set(s);
+                        }
+                    }
+
+                    // Parse `#[serde(rename_compact = "foo")]`
+                    MetaItem(NameValue(ref name, ref lit)) if name == "rename_compact" => {
+                        if let Ok(s) = get_string_from_lit(cx, name.as_ref(), name.as_ref(), lit) {
+                            ser_name_compact.set(s);
+                        }
+                    }
+
                     // Parse `#[serde(default)]`
                     MetaItem(Word(ref name)) if name == "default" => {
                         default.set(Default::Default);
@@ -725,10 +1520,19 @@ impl Field {
                         skip_deserializing.set_true();
                     },
 
-                    // Parse `#[serde(skip_serializing_if = "...")]`
+                    // Parse `#[serde(skip_serializing_if = "...")]` where the
+                    // predicate is either a path or a closure literal
                     MetaItem(NameValue(ref name, ref lit)) if name == "skip_serializing_if" => {
+                        if let Ok(predicate) = parse_lit_into_predicate(cx, name.as_ref(), lit) {
+                            skip_serializing_if.set(predicate);
+                        }
+                    }
+
+                    // Parse `#[serde(skip_serializing_unless_serializer = "...")]`
+                    MetaItem(NameValue(ref name, ref lit))
+                        if name == "skip_serializing_unless_serializer" => {
                         if let Ok(path) = parse_lit_into_path(cx, name.as_ref(), lit) {
-                            skip_serializing_if.set(path);
+                            skip_serializing_unless_serializer.set(path);
                         }
                     }
 
@@ -739,6 +1543,47 @@ impl Field {
                         }
                     }
 
+                    // Parse `#[serde(float_precision = 2)]`
+                    MetaItem(NameValue(ref name, ref lit)) if name == "float_precision" => {
+                        if let Ok(precision) = get_usize_from_lit(cx, name.as_ref(), lit) {
+                            float_precision.set(precision);
+                        }
+                    }
+
+                    // Parse `#[serde(int128_as_string)]`
+                    MetaItem(Word(ref name)) if name == "int128_as_string" => {
+                        int128_as_string.set_true();
+                    }
+
+                    // Parse `#[serde(char_as_str)]`
+                    MetaItem(Word(ref name)) if name == "char_as_str" => {
+                        char_as_str.set_true();
+                    }
+
+                    // Parse `#[serde(raw)]`
+                    MetaItem(Word(ref name)) if name == "raw" => {
+                        raw.set_true();
+                    }
+
+                    // Parse `#[serde(serialize_bytes)]`
+                    MetaItem(Word(ref name)) if name == "serialize_bytes" => {
+                        serialize_bytes.set_true();
+                    }
+
+                    // Parse `#[serde(description = "...")]`
+                    MetaItem(NameValue(ref name, ref lit)) if name == "description" => {
+                        if let Ok(s) = get_string_from_lit(cx, name.as_ref(), name.as_ref(), lit) {
+                            description.set(s);
+                        }
+                    }
+
+                    // Parse `#[serde(null_sentinel = "-1")]`
+                    MetaItem(NameValue(ref name, ref lit)) if name == "null_sentinel" => {
+                        if let Ok(tts) = parse_lit_into_tokens(cx, name.as_ref(), lit) {
+                            null_sentinel.set(tts);
+                        }
+                    }
+
                     // Parse `#[serde(deserialize_with = "...")]`
                     MetaItem(NameValue(ref name, ref lit)) if name == "deserialize_with" => {
                         if let Ok(path) = parse_lit_into_path(cx, name.as_ref(), lit) {
@@ -809,6 +1654,11 @@ impl Field {
                         }
                     }
 
+                    // Parse `#[serde(flatten)]`
+                    MetaItem(Word(ref name)) if name == "flatten" => {
+                        flatten.set_true();
+                    }
+
                     MetaItem(ref meta_item) => {
                         cx.error(format!("unknown serde field attribute `{}`", meta_item.name()),);
                     }
@@ -854,16 +1704,51 @@ impl Field {
         let ser_renamed = ser_name.is_some();
         let de_name = de_name.get();
         let de_renamed = de_name.is_some();
+        let ser_name_const = ser_name_const.get();
+        if ser_renamed && ser_name_const.is_some() {
+            cx.error(
+                format!(
+                    "field `{}` cannot have both #[serde(rename = \"...\")] and \
+                     #[serde(rename_const = \"...\")] for serialization",
+                    ident
+                ),
+            );
+        }
+        let ser_name_hr = ser_name_hr.get();
+        let ser_name_compact = ser_name_compact.get();
+        if ser_name_const.is_some() && (ser_name_hr.is_some() || ser_name_compact.is_some()) {
+            cx.error(
+                format!(
+                    "field `{}` cannot have both #[serde(rename_const = \"...\")] and \
+                     #[serde(rename_hr = \"...\")] or #[serde(rename_compact = \"...\")]",
+                    ident
+                ),
+            );
+        }
+        // Deserialize cannot tell which of the names by readability was used,
+        // so unless it is given a name of its own it accepts both.
+        if !de_renamed {
+            for name in ser_name_hr.iter().chain(ser_name_compact.iter()) {
+                if *name != ident && !de_aliases.contains(name) {
+                    de_aliases.push(name.clone());
+                }
+            }
+        }
         Field {
             name: Name {
                 serialize: ser_name.unwrap_or_else(|| ident.clone()),
                 deserialize: de_name.unwrap_or(ident),
             },
+            de_aliases: de_aliases,
+            ser_name_const: ser_name_const,
+            ser_name_hr: ser_name_hr,
+            ser_name_compact: ser_name_compact,
             ser_renamed: ser_renamed,
             de_renamed: de_renamed,
             skip_serializing: skip_serializing.get(),
             skip_deserializing: skip_deserializing.get(),
             skip_serializing_if: skip_serializing_if.get(),
+            skip_serializing_unless_serializer: skip_serializing_unless_serializer.get(),
             default: default.get().unwrap_or(Default::None),
             serialize_with: serialize_with.get(),
             deserialize_with: deserialize_with.get(),
@@ -871,6 +1756,14 @@ impl Field {
             de_bound: de_bound.get(),
             borrowed_lifetimes: borrowed_lifetimes,
             getter: getter.get(),
+            flatten: flatten.get(),
+            float_precision: float_precision.get(),
+            int128_as_string: int128_as_string.get(),
+            char_as_str: char_as_str.get(),
+            raw: raw.get(),
+            serialize_bytes: serialize_bytes.get(),
+            description: description.get(),
+            null_sentinel: null_sentinel.get(),
         }
     }
 
@@ -887,6 +1780,33 @@ impl Field {
         }
     }
 
+    /// Additional names accepted for this field when deserializing, from
+    /// `#[serde(rename("new_name", "old_name", ...))]`.
+    pub fn deserialize_aliases(&self) -> &[String] {
+        &self.de_aliases
+    }
+
+    /// Path to a `&'static str` constant that overrides the serialized name
+    /// of this field. Such fields cannot be deserialized.
+    pub fn serialize_name_const(&self) -> Option<&syn::Path> {
+        self.ser_name_const.as_ref()
+    }
+
+    /// The serialized names of this field for human readable and for compact
+    /// serializers, from `#[serde(rename_hr = "...", rename_compact = "...")]`.
+    /// Whichever one is not given falls back to the regular serialized name.
+    /// Both are also accepted as aliases when deserializing, unless the field
+    /// is renamed for deserialization.
+    pub fn serialize_names_by_readability(&self) -> Option<(&str, &str)> {
+        if self.ser_name_hr.is_none() && self.ser_name_compact.is_none() {
+            return None;
+        }
+        let name = &self.name.serialize;
+        let hr = self.ser_name_hr.as_ref().unwrap_or(name);
+        let compact = self.ser_name_compact.as_ref().unwrap_or(name);
+        Some((hr, compact))
+    }
+
     pub fn skip_serializing(&self) -> bool {
         self.skip_serializing
     }
@@ -895,10 +1815,17 @@ impl Field {
         self.skip_deserializing
     }
 
-    pub fn skip_serializing_if(&self) -> Option<&syn::Path> {
+    pub fn skip_serializing_if(&self) -> Option<&SkipPredicate> {
         self.skip_serializing_if.as_ref()
     }
 
+    /// Path to a function `fn<S: Serializer>(&S) -> bool` that is called with
+    /// the serializer before serialization starts. The field is skipped if it
+    /// returns false.
+    pub fn skip_serializing_unless_serializer(&self) -> Option<&syn::Path> {
+        self.skip_serializing_unless_serializer.as_ref()
+    }
+
     pub fn default(&self) -> &Default {
         &self.default
     }
@@ -926,6 +1853,57 @@ impl Field {
     pub fn getter(&self) -> Option<&syn::Path> {
         self.getter.as_ref()
     }
+
+    pub fn flatten(&self) -> bool {
+        self.flatten
+    }
+
+    /// Number of decimals a float field is rounded to. The field is
+    /// serialized as a string.
+    pub fn float_precision(&self) -> Option<usize> {
+        self.float_precision
+    }
+
+    /// Whether an `i128` or `u128` field is serialized as a decimal string,
+    /// for serializers without 128-bit integer support.
+    pub fn int128_as_string(&self) -> bool {
+        self.int128_as_string
+    }
+
+    /// Whether a `char` field is serialized as a string of one character
+    /// instead of through `serialize_char`.
+    pub fn char_as_str(&self) -> bool {
+        self.char_as_str
+    }
+
+    /// Whether the field holds already serialized content that is passed to
+    /// the serializer as a newtype struct named
+    /// `serde::private::ser::RAW_VALUE_TOKEN`. Serializers that recognize the
+    /// name emit the string verbatim, all others serialize it as a string.
+    pub fn raw(&self) -> bool {
+        self.raw
+    }
+
+    /// Whether the field, which must implement `AsRef<[u8]>`, is passed to
+    /// `Serializer::serialize_bytes` instead of being serialized as a sequence
+    /// of integers. Serializers without a native byte representation still
+    /// get to choose their own fallback, typically a sequence.
+    pub fn serialize_bytes(&self) -> bool {
+        self.serialize_bytes
+    }
+
+    /// Human readable description of the field, listed in the generated
+    /// `FIELD_DOCS` const.
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_ref().map(|s| &s[..])
+    }
+
+    /// Expression serialized in place of an `Option` field that is `None`.
+    /// A field that is `Some` is serialized as its content, without going
+    /// through `serialize_some`.
+    pub fn null_sentinel(&self) -> Option<&[syn::TokenTree]> {
+        self.null_sentinel.as_ref().map(|tts| &tts[..])
+    }
 }
 
 type SerAndDe<T> = (Option<T>, Option<T>);
@@ -976,6 +1954,27 @@ fn get_renames(cx: &Ctxt, items: &[syn::NestedMetaItem]) -> Result<SerAndDe<Stri
     get_ser_and_de(cx, "rename", items, get_string_from_lit)
 }
 
+fn is_literal_list(items: &[syn::NestedMetaItem]) -> bool {
+    match items.first() {
+        Some(&Literal(_)) => true,
+        _ => false,
+    }
+}
+
+fn get_rename_chain(cx: &Ctxt, items: &[syn::NestedMetaItem]) -> Result<Vec<String>, ()> {
+    let mut names = Vec::new();
+    for item in items {
+        match *item {
+            Literal(ref lit) => names.push(try!(get_string_from_lit(cx, "rename", "rename", lit))),
+            MetaItem(_) => {
+                cx.error("malformed rename attribute, expected `rename(\"new_name\", \"old_name\", ...)`");
+                return Err(());
+            }
+        }
+    }
+    Ok(names)
+}
+
 fn get_where_predicates(
     cx: &Ctxt,
     items: &[syn::NestedMetaItem],
@@ -983,6 +1982,46 @@ fn get_where_predicates(
     get_ser_and_de(cx, "bound", items, parse_lit_into_where)
 }
 
+fn get_computed(cx: &Ctxt, items: &[syn::NestedMetaItem]) -> Result<Computed, ()> {
+    let mut name = Attr::none(cx, "computed name");
+    let mut value = Attr::none(cx, "computed value");
+
+    for item in items {
+        match *item {
+            MetaItem(NameValue(ref meta_name, ref lit)) if meta_name == "name" => {
+                if let Ok(s) = get_string_from_lit(cx, "computed", meta_name.as_ref(), lit) {
+                    name.set(s);
+                }
+            }
+
+            MetaItem(NameValue(ref meta_name, ref lit)) if meta_name == "value" => {
+                if let Ok(path) = parse_lit_into_path(cx, "computed", lit) {
+                    value.set(path);
+                }
+            }
+
+            _ => {
+                cx.error(
+                    "malformed computed attribute, expected \
+                     `computed(name = \"...\", value = \"...\")`",
+                );
+                return Err(());
+            }
+        }
+    }
+
+    match (name.get(), value.get()) {
+        (Some(name), Some(value)) => Ok(Computed { name: name, value: value }),
+        _ => {
+            cx.error(
+                "malformed computed attribute, expected \
+                 `computed(name = \"...\", value = \"...\")`",
+            );
+            Err(())
+        }
+    }
+}
+
 pub fn get_serde_meta_items(attr: &syn::Attribute) -> Option<Vec<syn::NestedMetaItem>> {
     match attr.value {
         List(ref name, ref items) if name == "serde" => Some(items.iter().cloned().collect()),
@@ -1010,11 +2049,93 @@ fn get_string_from_lit(
     }
 }
 
+fn get_usize_from_lit(cx: &Ctxt, attr_name: &str, lit: &syn::Lit) -> Result<usize, ()> {
+    if let syn::Lit::Int(value, _) = *lit {
+        Ok(value as usize)
+    } else {
+        cx.error(
+            format!(
+                "expected serde {} attribute to be an integer: `{} = 2`",
+                attr_name,
+                attr_name
+            ),
+        );
+        Err(())
+    }
+}
+
 fn parse_lit_into_path(cx: &Ctxt, attr_name: &str, lit: &syn::Lit) -> Result<syn::Path, ()> {
     let string = try!(get_string_from_lit(cx, attr_name, attr_name, lit));
     syn::parse_path(&string).map_err(|err| cx.error(err))
 }
 
+// Parses a string literal containing either a path to a function, like
+// "Vec::is_empty", or a closure literal, like "|v| v.len() > 10".
+fn parse_lit_into_predicate(
+    cx: &Ctxt,
+    attr_name: &str,
+    lit: &syn::Lit,
+) -> Result<SkipPredicate, ()> {
+    let string = try!(get_string_from_lit(cx, attr_name, attr_name, lit));
+
+    if let Ok(path) = syn::parse_path(&string) {
+        return Ok(SkipPredicate::Path(path));
+    }
+
+    let tts = try!(parse_lit_into_tokens(cx, attr_name, lit));
+    if is_closure(&tts) {
+        Ok(SkipPredicate::Closure(tts))
+    } else {
+        cx.error(
+            format!(
+                "expected serde {} attribute to be a path or a closure: {:?}",
+                attr_name,
+                string
+            ),
+        );
+        Err(())
+    }
+}
+
+// Whether the tokens start like a closure: `|args| body`, `|| body` or
+// `move |args| body`. The rest is left for the compiler to check.
+fn is_closure(tts: &[syn::TokenTree]) -> bool {
+    let tts = match tts.first() {
+        Some(&syn::TokenTree::Token(syn::Token::Ident(ref ident))) if ident == "move" => {
+            &tts[1..]
+        }
+        _ => tts,
+    };
+    match tts.first() {
+        Some(&syn::TokenTree::Token(syn::Token::BinOp(syn::BinOpToken::Or))) |
+        Some(&syn::TokenTree::Token(syn::Token::OrOr)) => true,
+        _ => false,
+    }
+}
+
+// Expressions are kept as tokens because parsing them requires the "full"
+// feature of syn, which would slow down compiling every crate that uses the
+// derive. The compiler checks them once they are part of the generated code.
+fn parse_lit_into_tokens(
+    cx: &Ctxt,
+    attr_name: &str,
+    lit: &syn::Lit,
+) -> Result<Vec<syn::TokenTree>, ()> {
+    let string = try!(get_string_from_lit(cx, attr_name, attr_name, lit));
+
+    match syn::parse_token_trees(&string) {
+        Ok(ref tts) if tts.is_empty() => {
+            cx.error(format!("expected serde {} attribute to not be empty", attr_name));
+            Err(())
+        }
+        Ok(tts) => Ok(tts),
+        Err(err) => {
+            cx.error(format!("failed to parse {} = {:?}: {}", attr_name, string, err));
+            Err(())
+        }
+    }
+}
+
 fn parse_lit_into_where(
     cx: &Ctxt,
     attr_name: &str,
@@ -7,7 +7,8 @@
 // except according to those terms.
 
 use ast::{Body, Container, Style};
-use attr::Identifier;
+use attr::{EnumTag, Identifier, NewtypeOption, SingleVariant};
+use syn;
 use Ctxt;
 
 /// Cross-cutting checks that require looking at more than a single attrs
@@ -15,6 +16,29 @@ use Ctxt;
 pub fn check(cx: &Ctxt, cont: &Container) {
     check_getter(cx, cont);
     check_identifier(cx, cont);
+    check_flags(cx, cont);
+    check_flatten(cx, cont);
+    check_as_seq(cx, cont);
+    check_variant_tag(cx, cont);
+    check_unit_as_null(cx, cont);
+    check_serializer_gates(cx, cont);
+    check_float_precision(cx, cont);
+    check_int128_as_string(cx, cont);
+    check_char_as_str(cx, cont);
+    check_raw(cx, cont);
+    check_serialize_bytes(cx, cont);
+    check_description(cx, cont);
+    check_null_sentinel(cx, cont);
+    check_newtype_option(cx, cont);
+    check_names_by_readability(cx, cont);
+    check_external_as_map(cx, cont);
+    check_single_variant(cx, cont);
+    check_field_order(cx, cont);
+    check_skip_if_all_fields_skipped(cx, cont);
+    check_transparent(cx, cont);
+    check_conflicting_representations(cx, cont);
+    check_struct_tag(cx, cont);
+    check_serialize_trait_objects(cx, cont);
 }
 
 /// Getters are only allowed inside structs (not enums) with the `remote`
@@ -38,7 +62,8 @@ fn check_getter(cx: &Ctxt, cont: &Container) {
 }
 
 /// The `other` attribute must be used at most once and it must be the last
-/// variant of an enum that has the `field_identifier` attribute.
+/// variant of an enum that has the `field_identifier` attribute or of an enum
+/// that is not untagged and not an identifier, where it catches unknown tags.
 ///
 /// Inside a `variant_identifier` all variants must be unit variants. Inside a
 /// `field_identifier` all but possibly one variant must be unit variants. The
@@ -51,23 +76,34 @@ fn check_identifier(cx: &Ctxt, cont: &Container) {
         }
     };
 
+    let is_untagged = match *cont.attrs.tag() {
+        EnumTag::None => true,
+        _ => false,
+    };
+
     for (i, variant) in variants.iter().enumerate() {
         match (variant.style, cont.attrs.identifier(), variant.attrs.other()) {
-            // The `other` attribute may only be used in a field_identifier.
-            (_, Identifier::Variant, true) |
-            (_, Identifier::No, true) => {
-                cx.error("#[serde(other)] may only be used inside a field_identifier");
+            // The `other` attribute may not be used in a variant_identifier.
+            (_, Identifier::Variant, true) => {
+                cx.error("#[serde(other)] may not be used inside a variant_identifier");
+            }
+
+            // An untagged enum has no tag for `other` to catch.
+            (_, Identifier::No, true) if is_untagged => {
+                cx.error("#[serde(other)] may not be used inside an untagged enum");
             }
 
             // Variant with `other` attribute must be the last one.
-            (Style::Unit, Identifier::Field, true) => {
+            (Style::Unit, Identifier::Field, true) |
+            (Style::Unit, Identifier::No, true) => {
                 if i < variants.len() - 1 {
                     cx.error("#[serde(other)] must be the last variant");
                 }
             }
 
             // Variant with `other` attribute must be a unit variant.
-            (_, Identifier::Field, true) => {
+            (_, Identifier::Field, true) |
+            (_, Identifier::No, true) => {
                 cx.error("#[serde(other)] must be on a unit variant");
             }
 
@@ -94,3 +130,861 @@ fn check_identifier(cx: &Ctxt, cont: &Container) {
         }
     }
 }
+
+/// Every serialized field of a struct with `#[serde(as_flags)]` must be a
+/// `bool`, as the struct is serialized as the list of names of the fields that
+/// are `true`. Whether a field is listed depends only on its value, so it
+/// cannot be skipped conditionally.
+fn check_flags(cx: &Ctxt, cont: &Container) {
+    if !cont.attrs.as_flags() {
+        return;
+    }
+
+    for field in cont.body.all_fields() {
+        if field.attrs.skip_serializing() {
+            continue;
+        }
+        let name = match field.ident {
+            Some(ref ident) => ident.to_string(),
+            None => field.attrs.name().serialize_name(),
+        };
+        if *field.ty != syn::parse_type("bool").unwrap() {
+            cx.error(format!("#[serde(as_flags)] requires field `{}` to be a bool", name));
+        }
+        let skips = [
+            ("skip_serializing_if", field.attrs.skip_serializing_if().is_some()),
+            (
+                "skip_serializing_unless_serializer",
+                field.attrs.skip_serializing_unless_serializer().is_some(),
+            ),
+        ];
+        for &(skip, present) in &skips {
+            if present {
+                cx.error(
+                    format!(
+                        "#[serde(as_flags)] cannot skip field `{}` with #[serde({})]",
+                        name,
+                        skip
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/// Flattened fields are spliced into the map of the enclosing struct, or into
+/// the sequence of the enclosing tuple struct, so they are only supported in
+/// structs with named fields and in tuple structs.
+fn check_flatten(cx: &Ctxt, cont: &Container) {
+    if let Body::Enum(ref variants) = cont.body {
+        for variant in variants.iter().filter(|variant| variant.attrs.flatten()) {
+            match (cont.attrs.tag(), variant.style) {
+                (&EnumTag::Internal { .. }, Style::Newtype) => {}
+                _ => {
+                    cx.error(
+                        format!(
+                            "#[serde(flatten)] on variant `{}` requires a newtype variant \
+                             in an internally tagged enum",
+                            variant.ident
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    if !cont.body.all_fields().any(|field| field.attrs.flatten()) {
+        return;
+    }
+
+    let in_tuple_struct = match cont.body {
+        Body::Struct(Style::Struct, _) => false,
+        Body::Struct(Style::Tuple, _) => true,
+        Body::Enum(ref variants) => {
+            for variant in variants {
+                if !variant.fields.iter().any(|field| field.attrs.flatten()) {
+                    continue;
+                }
+                let is_struct = match variant.style {
+                    Style::Struct => true,
+                    Style::Tuple | Style::Newtype | Style::Unit => false,
+                };
+                if !is_struct || variant.attrs.as_seq() {
+                    cx.error(
+                        format!(
+                            "#[serde(flatten)] on a field of variant `{}` requires a struct \
+                             variant without #[serde(as_seq)]",
+                            variant.ident
+                        ),
+                    );
+                }
+            }
+            false
+        }
+        Body::Struct(_, _) => {
+            cx.error(
+                "#[serde(flatten)] can only be used on fields of structs with named fields \
+                 or of tuple structs",
+            );
+            return;
+        }
+    };
+
+    if cont.attrs.partial() {
+        cx.error("#[serde(flatten)] cannot be used in a struct with #[serde(partial)]");
+    }
+    if cont.attrs.tuple_names().is_some() {
+        cx.error("#[serde(flatten)] cannot be used in a struct with #[serde(tuple_names)]");
+    }
+
+    for field in cont.body.all_fields() {
+        if !field.attrs.flatten() {
+            continue;
+        }
+        let name = match field.ident {
+            Some(ref ident) => ident.to_string(),
+            None => field.attrs.name().serialize_name(),
+        };
+        if in_tuple_struct {
+            if !is_seq_flattenable(field.ty) {
+                cx.error(
+                    format!(
+                        "#[serde(flatten)] cannot be used on field `{}`, flattened fields of a \
+                         tuple struct must serialize as a tuple or sequence",
+                        name
+                    ),
+                );
+            }
+        } else if !is_flattenable(field.ty) {
+            cx.error(
+                format!(
+                    "#[serde(flatten)] cannot be used on field `{}`, flattened fields must \
+                     serialize as a struct or map",
+                    name
+                ),
+            );
+        }
+    }
+}
+
+/// Types that are known not to serialize as a struct or map. Anything else
+/// is checked when the value is serialized.
+fn is_flattenable(ty: &syn::Ty) -> bool {
+    match *ty {
+        syn::Ty::Slice(_) | syn::Ty::Array(_, _) | syn::Ty::Tup(_) | syn::Ty::Never => false,
+        syn::Ty::Rptr(_, ref mut_ty) => is_flattenable(&mut_ty.ty),
+        syn::Ty::Path(None, ref path) => {
+            match path.segments.last() {
+                Some(segment) if path.segments.len() == 1 => {
+                    match segment.ident.as_ref() {
+                        "bool" | "char" | "str" | "String" | "i8" | "i16" | "i32" | "i64" |
+                        "isize" | "u8" | "u16" | "u32" | "u64" | "usize" | "f32" | "f64" |
+                        "Vec" => false,
+                        _ => true,
+                    }
+                }
+                _ => true,
+            }
+        }
+        _ => true,
+    }
+}
+
+/// Types that are known not to serialize as a tuple or sequence. Anything
+/// else is checked when the value is serialized.
+fn is_seq_flattenable(ty: &syn::Ty) -> bool {
+    match *ty {
+        syn::Ty::Never => false,
+        syn::Ty::Rptr(_, ref mut_ty) => is_seq_flattenable(&mut_ty.ty),
+        syn::Ty::Path(None, ref path) => {
+            match path.segments.last() {
+                Some(segment) if path.segments.len() == 1 => {
+                    match segment.ident.as_ref() {
+                        "bool" | "char" | "str" | "String" | "i8" | "i16" | "i32" | "i64" |
+                        "isize" | "u8" | "u16" | "u32" | "u64" | "usize" | "f32" | "f64" |
+                        "HashMap" | "BTreeMap" => false,
+                        _ => true,
+                    }
+                }
+                _ => true,
+            }
+        }
+        _ => true,
+    }
+}
+
+/// Struct variants with `#[serde(as_seq)]` are serialized as tuple variants,
+/// which only the externally tagged representation can express.
+fn check_as_seq(cx: &Ctxt, cont: &Container) {
+    let variants = match cont.body {
+        Body::Enum(ref variants) => variants,
+        Body::Struct(_, _) => return,
+    };
+
+    if let EnumTag::External = *cont.attrs.tag() {
+        if cont.attrs.external_as_map() && variants.iter().any(|variant| variant.attrs.as_seq()) {
+            cx.error("#[serde(as_seq)] cannot be used in an enum with #[serde(external_as_map)]");
+        }
+        return;
+    }
+
+    for variant in variants {
+        if variant.attrs.as_seq() {
+            cx.error(
+                format!(
+                    "#[serde(as_seq)] on variant `{}` requires an externally tagged enum",
+                    variant.ident
+                ),
+            );
+        }
+    }
+}
+
+/// A variant with its own `#[serde(tag = "...")]` is internally tagged within
+/// an otherwise externally tagged enum. It replaces the external tag, so it
+/// cannot be combined with another enum representation or with `as_seq`.
+fn check_variant_tag(cx: &Ctxt, cont: &Container) {
+    let variants = match cont.body {
+        Body::Enum(ref variants) => variants,
+        Body::Struct(_, _) => return,
+    };
+
+    let externally_tagged = match *cont.attrs.tag() {
+        EnumTag::External => !cont.attrs.external_as_map() && !cont.attrs.transparent(),
+        EnumTag::Internal { .. } | EnumTag::Adjacent { .. } | EnumTag::None => false,
+    };
+
+    for variant in variants.iter().filter(|variant| variant.attrs.tag().is_some()) {
+        if !externally_tagged {
+            cx.error(
+                format!(
+                    "#[serde(tag = \"...\")] on variant `{}` requires an externally tagged enum",
+                    variant.ident
+                ),
+            );
+        }
+        if variant.attrs.as_seq() {
+            cx.error(
+                format!(
+                    "variant `{}` cannot have both #[serde(tag = \"...\")] and #[serde(as_seq)]",
+                    variant.ident
+                ),
+            );
+        }
+    }
+}
+
+/// A unit variant with `#[serde(unit_as_null)]` is serialized without its name,
+/// which only matches the representation of the other variants if they are
+/// externally tagged or untagged.
+fn check_unit_as_null(cx: &Ctxt, cont: &Container) {
+    let variants = match cont.body {
+        Body::Enum(ref variants) => variants,
+        Body::Struct(_, _) => return,
+    };
+
+    let supported = match *cont.attrs.tag() {
+        EnumTag::External => !cont.attrs.external_as_map(),
+        EnumTag::None => true,
+        EnumTag::Internal { .. } | EnumTag::Adjacent { .. } => false,
+    };
+
+    for variant in variants.iter().filter(|variant| variant.attrs.unit_as_null()) {
+        if !supported {
+            cx.error(
+                format!(
+                    "#[serde(unit_as_null)] on variant `{}` requires an externally tagged or \
+                     untagged enum",
+                    variant.ident
+                ),
+            );
+        }
+    }
+}
+
+/// The predicates of `skip_serializing_unless_serializer` are evaluated before
+/// the serializer is consumed, which is supported for structs with named
+/// fields only.
+fn check_serializer_gates(cx: &Ctxt, cont: &Container) {
+    if let Body::Struct(Style::Struct, _) = cont.body {
+        return;
+    }
+
+    if cont.body
+           .all_fields()
+           .any(|field| field.attrs.skip_serializing_unless_serializer().is_some()) {
+        cx.error(
+            "#[serde(skip_serializing_unless_serializer = \"...\")] can only be used on \
+             fields of structs with named fields",
+        );
+    }
+}
+
+/// `#[serde(float_precision = N)]` formats the field with `{:.N}`, so the
+/// field must be an `f32` or `f64` and cannot also have a `serialize_with`.
+fn check_float_precision(cx: &Ctxt, cont: &Container) {
+    for field in cont.body.all_fields() {
+        if field.attrs.float_precision().is_none() {
+            continue;
+        }
+
+        let name = field.attrs.name().serialize_name();
+        if !field.is_primitive(&["f32", "f64"]) {
+            cx.error(
+                format!(
+                    "#[serde(float_precision)] requires field `{}` to be an f32 or f64",
+                    name
+                ),
+            );
+        }
+        if field.attrs.serialize_with().is_some() {
+            cx.error(
+                format!(
+                    "field `{}` cannot have both #[serde(float_precision)] and \
+                     #[serde(serialize_with = \"...\")]",
+                    name
+                ),
+            );
+        }
+    }
+}
+
+/// `#[serde(int128_as_string)]` formats the field with `Display`, so the
+/// field must be an `i128` or `u128` and cannot have any other serialization
+/// override.
+fn check_int128_as_string(cx: &Ctxt, cont: &Container) {
+    for field in cont.body.all_fields() {
+        if !field.attrs.int128_as_string() {
+            continue;
+        }
+
+        let name = field.attrs.name().serialize_name();
+        if !field.is_primitive(&["i128", "u128"]) {
+            cx.error(
+                format!(
+                    "#[serde(int128_as_string)] requires field `{}` to be an i128 or u128",
+                    name
+                ),
+            );
+        }
+        if field.attrs.serialize_with().is_some() || field.attrs.float_precision().is_some() {
+            cx.error(
+                format!(
+                    "field `{}` cannot have #[serde(int128_as_string)] together with \
+                     #[serde(serialize_with = \"...\")] or #[serde(float_precision)]",
+                    name
+                ),
+            );
+        }
+    }
+}
+
+/// `#[serde(null_sentinel = "...")]` stands in for `None`, so the field must
+/// be an `Option` and cannot have any other serialization override.
+fn check_null_sentinel(cx: &Ctxt, cont: &Container) {
+    for field in cont.body.all_fields() {
+        if field.attrs.null_sentinel().is_none() {
+            continue;
+        }
+
+        let name = field.attrs.name().serialize_name();
+        if !field.is_option() {
+            cx.error(
+                format!(
+                    "#[serde(null_sentinel = \"...\")] requires field `{}` to be an Option",
+                    name
+                ),
+            );
+        }
+        if field.attrs.serialize_with().is_some() || field.attrs.float_precision().is_some() ||
+           field.attrs.int128_as_string() {
+            cx.error(
+                format!(
+                    "field `{}` cannot have #[serde(null_sentinel = \"...\")] together with \
+                     another serialization override",
+                    name
+                ),
+            );
+        }
+    }
+}
+
+/// `#[serde(newtype_option = "...")]` only makes sense when the newtype wraps
+/// an `Option`. An inlined newtype is serialized without any name, so it
+/// cannot have `#[serde(newtype_name = "...")]` either.
+fn check_newtype_option(cx: &Ctxt, cont: &Container) {
+    if cont.attrs.newtype_option().is_none() {
+        return;
+    }
+
+    if cont.attrs.newtype_option() == Some(NewtypeOption::Inline) &&
+       cont.attrs.newtype_name().is_some() {
+        cx.error(
+            "#[serde(newtype_name = \"...\")] cannot be used together with \
+             #[serde(newtype_option = \"inline\")]",
+        );
+    }
+
+    if let Body::Struct(Style::Newtype, ref fields) = cont.body {
+        if !fields[0].is_option() {
+            cx.error("#[serde(newtype_option = \"...\")] requires the newtype to wrap an Option");
+        }
+    }
+}
+
+/// `Serialize` has a generic method, so it cannot be made into a trait object.
+/// A field like `Box<Serialize>` would otherwise fail with an object safety
+/// error pointing at the derive rather than at the field.
+fn check_serialize_trait_objects(cx: &Ctxt, cont: &Container) {
+    for field in cont.body.all_fields() {
+        if contains_serialize_trait_object(field.ty) {
+            let name = match field.ident {
+                Some(ref ident) => ident.to_string(),
+                None => field.attrs.name().serialize_name(),
+            };
+            cx.error(
+                format!(
+                    "field `{}` contains a `Serialize` trait object, which is not supported \
+                     because `Serialize` is not object safe; consider a generic type parameter",
+                    name
+                ),
+            );
+        }
+    }
+}
+
+fn contains_serialize_trait_object(ty: &syn::Ty) -> bool {
+    match *ty {
+        syn::Ty::TraitObject(ref bounds) => {
+            bounds.iter().any(
+                |bound| match *bound {
+                    syn::TyParamBound::Trait(ref poly, _) => is_serialize_path(&poly.trait_ref),
+                    syn::TyParamBound::Region(_) => false,
+                },
+            )
+        }
+        syn::Ty::Path(_, ref path) => {
+            path.segments.iter().any(
+                |segment| match segment.parameters {
+                    syn::PathParameters::AngleBracketed(ref data) => {
+                        data.types.iter().any(
+                            |ty| match *ty {
+                                syn::Ty::Path(None, ref path) => {
+                                    is_serialize_path(path) || contains_serialize_trait_object(ty)
+                                }
+                                _ => contains_serialize_trait_object(ty),
+                            },
+                        )
+                    }
+                    syn::PathParameters::Parenthesized(_) => false,
+                },
+            )
+        }
+        syn::Ty::Rptr(_, ref mut_ty) |
+        syn::Ty::Ptr(ref mut_ty) => {
+            match mut_ty.ty {
+                syn::Ty::Path(None, ref path) if is_serialize_path(path) => true,
+                ref ty => contains_serialize_trait_object(ty),
+            }
+        }
+        syn::Ty::Slice(ref ty) |
+        syn::Ty::Array(ref ty, _) |
+        syn::Ty::Paren(ref ty) => contains_serialize_trait_object(ty),
+        syn::Ty::Tup(ref tys) => tys.iter().any(contains_serialize_trait_object),
+        _ => false,
+    }
+}
+
+// A path naming the `Serialize` trait, which in type position other than at
+// the top level of a field is a trait object. Only a bare `Serialize` and the
+// paths into serde are recognized, so object safe traits of the same name
+// such as `erased_serde::Serialize` are not rejected.
+fn is_serialize_path(path: &syn::Path) -> bool {
+    if path.segments.iter().any(|segment| !segment.parameters.is_empty()) {
+        return false;
+    }
+
+    let idents: Vec<&str> = path.segments.iter().map(|segment| segment.ident.as_ref()).collect();
+    if !path.global && idents == ["Serialize"] {
+        return true;
+    }
+    idents == ["serde", "Serialize"] || idents == ["serde", "ser", "Serialize"]
+}
+
+/// `#[serde(char_as_str)]` replaces the serialization of a `char` field, so
+/// the field cannot have any other serialization override.
+fn check_char_as_str(cx: &Ctxt, cont: &Container) {
+    for field in cont.body.all_fields() {
+        if !field.attrs.char_as_str() {
+            continue;
+        }
+
+        let name = field.attrs.name().serialize_name();
+        if !field.is_primitive(&["char"]) {
+            cx.error(format!("#[serde(char_as_str)] requires field `{}` to be a char", name));
+        }
+        if field.attrs.serialize_with().is_some() || field.attrs.float_precision().is_some() ||
+           field.attrs.int128_as_string() || field.attrs.null_sentinel().is_some() {
+            cx.error(
+                format!(
+                    "field `{}` cannot have #[serde(char_as_str)] together with \
+                     another serialization override",
+                    name
+                ),
+            );
+        }
+    }
+}
+
+/// `#[serde(raw)]` replaces the serialization of the field, so the field
+/// cannot have any other serialization override.
+fn check_raw(cx: &Ctxt, cont: &Container) {
+    for field in cont.body.all_fields() {
+        if !field.attrs.raw() {
+            continue;
+        }
+
+        if field.attrs.serialize_with().is_some() || field.attrs.float_precision().is_some() ||
+           field.attrs.int128_as_string() || field.attrs.char_as_str() ||
+           field.attrs.null_sentinel().is_some() || field.attrs.flatten() {
+            cx.error(
+                format!(
+                    "field `{}` cannot have #[serde(raw)] together with \
+                     another serialization override",
+                    field.attrs.name().serialize_name()
+                ),
+            );
+        }
+    }
+}
+
+/// `#[serde(serialize_bytes)]` replaces the serialization of the field, so
+/// the field cannot have any other serialization override.
+fn check_serialize_bytes(cx: &Ctxt, cont: &Container) {
+    for field in cont.body.all_fields() {
+        if !field.attrs.serialize_bytes() {
+            continue;
+        }
+
+        if field.attrs.serialize_with().is_some() || field.attrs.float_precision().is_some() ||
+           field.attrs.int128_as_string() || field.attrs.char_as_str() || field.attrs.raw() ||
+           field.attrs.null_sentinel().is_some() || field.attrs.flatten() {
+            cx.error(
+                format!(
+                    "field `{}` cannot have #[serde(serialize_bytes)] together with \
+                     another serialization override",
+                    field.attrs.name().serialize_name()
+                ),
+            );
+        }
+    }
+}
+
+/// Field descriptions are collected into an inherent const keyed by field
+/// name, which only exists for structs with named fields.
+fn check_description(cx: &Ctxt, cont: &Container) {
+    if let Body::Struct(Style::Struct, _) = cont.body {
+        return;
+    }
+
+    if cont.body.all_fields().any(|field| field.attrs.description().is_some()) {
+        cx.error(
+            "#[serde(description = \"...\")] can only be used on fields of structs \
+             with named fields",
+        );
+    }
+}
+
+/// Picking between `rename_hr` and `rename_compact` asks the serializer
+/// whether it is human readable before serialization starts, which is only
+/// done for structs with named fields. The names are also not known
+/// statically so they cannot be interned.
+fn check_names_by_readability(cx: &Ctxt, cont: &Container) {
+    if !cont.body
+            .all_fields()
+            .any(|field| field.attrs.serialize_names_by_readability().is_some()) {
+        return;
+    }
+
+    if let Body::Struct(Style::Struct, _) = cont.body {
+        if cont.attrs.intern_keys() {
+            cx.error(
+                "#[serde(intern_keys)] cannot be used with \
+                 #[serde(rename_hr)] or #[serde(rename_compact)]",
+            );
+        }
+    } else {
+        cx.error(
+            "#[serde(rename_hr)] and #[serde(rename_compact)] can only be used on \
+             fields of structs with named fields",
+        );
+    }
+}
+
+/// `#[serde(external_as_map)]` replaces the externally tagged representation,
+/// so it cannot be combined with any other enum representation.
+fn check_external_as_map(cx: &Ctxt, cont: &Container) {
+    if !cont.attrs.external_as_map() {
+        return;
+    }
+
+    match *cont.attrs.tag() {
+        EnumTag::External => {}
+        EnumTag::Internal { .. } | EnumTag::Adjacent { .. } | EnumTag::None => {
+            cx.error("#[serde(external_as_map)] requires an externally tagged enum");
+        }
+    }
+}
+
+/// `#[serde(field_order = "...")]` sorts fields by their serialized name at
+/// compile time, which is not possible when the key is only known at runtime
+/// or a field contributes any number of entries. Entries that do not come from
+/// fields would not be part of the order, so they are rejected as well.
+fn check_field_order(cx: &Ctxt, cont: &Container) {
+    if cont.attrs.field_order().is_none() {
+        return;
+    }
+
+    let attrs = &cont.attrs;
+    let tagged = match *attrs.tag() {
+        EnumTag::Internal { .. } | EnumTag::Adjacent { .. } => true,
+        EnumTag::External | EnumTag::None => attrs.struct_tag().is_some(),
+    };
+    let conflicts = [
+        ("key_transform = \"...\"", attrs.key_transform().is_some()),
+        ("tag = \"...\"", tagged),
+        ("computed(...)", !attrs.computed().is_empty()),
+        ("tuple_names = \"...\"", attrs.tuple_names().is_some()),
+    ];
+    for &(name, present) in &conflicts {
+        if present {
+            cx.error(
+                format!(
+                    "#[serde(field_order = \"...\")] cannot be used together with \
+                     #[serde({})]",
+                    name
+                ),
+            );
+        }
+    }
+
+    for field in cont.body.all_fields() {
+        let name = field.attrs.name().serialize_name();
+        if field.attrs.flatten() || field.attrs.serialize_names_by_readability().is_some() {
+            cx.error(
+                format!(
+                    "#[serde(field_order = \"...\")] requires field `{}` to have a single \
+                     serialized name",
+                    name
+                ),
+            );
+        }
+        if field.attrs.serialize_name_const().is_some() {
+            cx.error(
+                format!(
+                    "#[serde(field_order = \"...\")] requires the serialized name of field `{}` \
+                     to be known, so it cannot have #[serde(rename_const = \"...\")]",
+                    name
+                ),
+            );
+        }
+    }
+}
+
+/// The generated `__serde_is_empty` only looks at the fields, so it would
+/// report a struct as empty even though entries that do not come from fields
+/// are still serialized.
+fn check_skip_if_all_fields_skipped(cx: &Ctxt, cont: &Container) {
+    if !cont.attrs.skip_if_all_fields_skipped() {
+        return;
+    }
+
+    let attrs = &cont.attrs;
+    let conflicts = [
+        ("tag = \"...\"", attrs.struct_tag().is_some()),
+        ("computed(...)", !attrs.computed().is_empty()),
+    ];
+    for &(name, present) in &conflicts {
+        if present {
+            cx.error(
+                format!(
+                    "#[serde(skip_if_all_fields_skipped)] cannot be used together with \
+                     #[serde({})]",
+                    name
+                ),
+            );
+        }
+    }
+}
+
+/// `#[serde(single_variant = "...")]` chooses the representation of the only
+/// variant of an enum, so the enum must have exactly one variant and the
+/// choice must not contradict `#[serde(transparent)]`.
+fn check_single_variant(cx: &Ctxt, cont: &Container) {
+    let single_variant = match cont.attrs.single_variant() {
+        Some(single_variant) => single_variant,
+        None => return,
+    };
+
+    if let Body::Enum(ref variants) = cont.body {
+        if variants.len() != 1 {
+            cx.error("#[serde(single_variant = \"...\")] requires enum to have exactly one variant");
+        }
+    }
+
+    if single_variant == SingleVariant::Tagged && cont.attrs.transparent() {
+        cx.error(
+            "#[serde(single_variant = \"tagged\")] cannot be used together with \
+             #[serde(transparent)]",
+        );
+    }
+}
+
+/// A `#[serde(transparent)]` struct must have exactly one field, which is
+/// always serialized, and a `#[serde(transparent)]` enum must have exactly
+/// one variant, which is represented without any tag.
+fn check_transparent(cx: &Ctxt, cont: &Container) {
+    if !cont.attrs.transparent() {
+        return;
+    }
+
+    match cont.body {
+        Body::Enum(ref variants) => {
+            // Reported by `check_single_variant` for
+            // `#[serde(single_variant = "transparent")]`.
+            if variants.len() != 1 && cont.attrs.single_variant().is_none() {
+                cx.error("#[serde(transparent)] requires enum to have exactly one variant");
+            }
+            match *cont.attrs.tag() {
+                EnumTag::External => {}
+                EnumTag::Internal { .. } | EnumTag::Adjacent { .. } | EnumTag::None => {
+                    cx.error("#[serde(transparent)] cannot be used with a tagged or untagged enum");
+                }
+            }
+        }
+        Body::Struct(_, ref fields) => {
+            if fields.len() != 1 {
+                cx.error("#[serde(transparent)] requires struct to have exactly one field");
+            }
+            for field in fields {
+                let attrs = &field.attrs;
+                let skips = [
+                    ("skip_serializing", attrs.skip_serializing()),
+                    ("skip_serializing_if", attrs.skip_serializing_if().is_some()),
+                    (
+                        "skip_serializing_unless_serializer",
+                        attrs.skip_serializing_unless_serializer().is_some(),
+                    ),
+                ];
+                for &(name, present) in &skips {
+                    if present {
+                        cx.error(
+                            format!(
+                                "#[serde(transparent)] cannot skip field `{}` with #[serde({})]",
+                                attrs.name().serialize_name(),
+                                name
+                            ),
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The constant field of `#[serde(tag = "...")]` on a struct is only added
+/// when the struct is serialized field by field.
+fn check_struct_tag(cx: &Ctxt, cont: &Container) {
+    if cont.attrs.struct_tag().is_none() {
+        return;
+    }
+
+    let attrs = &cont.attrs;
+    let conflicts = [
+        ("into", attrs.into_type().is_some()),
+        ("transparent", attrs.transparent()),
+        ("scalar_field", attrs.scalar_field().is_some()),
+        ("as_flags", attrs.as_flags()),
+        ("partial", attrs.partial()),
+    ];
+    for &(name, present) in &conflicts {
+        if present {
+            cx.error(
+                format!(
+                    "#[serde(tag = \"...\")] on a struct cannot be used together with \
+                     #[serde({})]",
+                    name
+                ),
+            );
+        }
+    }
+}
+
+/// Each of these container attributes replaces the way the container is
+/// serialized (or deserialized) as a whole, so at most one of them may be
+/// given. Otherwise all but one would be silently ignored. The same goes for
+/// a few pairs of attributes where one is skipped by the code of the other.
+fn check_conflicting_representations(cx: &Ctxt, cont: &Container) {
+    let attrs = &cont.attrs;
+
+    let ser = [
+        ("into", attrs.into_type().is_some()),
+        ("transparent", attrs.transparent()),
+        ("scalar_field", attrs.scalar_field().is_some()),
+        ("as_flags", attrs.as_flags()),
+        ("tuple_names", attrs.tuple_names().is_some()),
+        ("key_transform", attrs.key_transform().is_some()),
+        ("external_as_map", attrs.external_as_map()),
+        ("as_empty_map", attrs.as_empty_map()),
+        ("unit_as_null", attrs.unit_as_null()),
+    ];
+    let de = [
+        ("from", attrs.from_type().is_some()),
+        ("transparent", attrs.transparent()),
+    ];
+
+    for group in &[&ser[..], &de[..]] {
+        let present: Vec<_> = group
+            .iter()
+            .filter(|&&(_, present)| present)
+            .map(|&(name, _)| name)
+            .collect();
+        for (i, first) in present.iter().enumerate() {
+            for second in &present[i + 1..] {
+                cx.error(
+                    format!(
+                        "#[serde({})] and #[serde({})] cannot be used together",
+                        first,
+                        second
+                    ),
+                );
+            }
+        }
+    }
+
+    // These do not replace the representation, but the second one bypasses
+    // the code that would handle the first.
+    let computed = ("computed", !attrs.computed().is_empty());
+    let partial = ("partial", attrs.partial());
+    let newtype_name = ("newtype_name", attrs.newtype_name().is_some());
+    let key_transform = ("key_transform", attrs.key_transform().is_some());
+    let pairs = [
+        (computed, ("scalar_field", attrs.scalar_field().is_some())),
+        (computed, ("as_flags", attrs.as_flags())),
+        (newtype_name, ("tuple_names", attrs.tuple_names().is_some())),
+        (key_transform, partial),
+        (computed, partial),
+    ];
+
+    for &((first, first_present), (second, second_present)) in &pairs {
+        if first_present && second_present {
+            cx.error(
+                format!(
+                    "#[serde({})] and #[serde({})] cannot be used together",
+                    first,
+                    second
+                ),
+            );
+        }
+    }
+}
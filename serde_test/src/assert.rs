@@ -95,6 +95,27 @@ where
     }
 }
 
+/// Asserts that `value` serializes to the given `tokens` when the serializer
+/// is not human readable.
+///
+/// This is the same as `assert_ser_tokens` except that
+/// `Serializer::is_human_readable` returns false, for types that serialize
+/// differently to compact formats.
+pub fn assert_ser_tokens_compact<T>(value: &T, tokens: &[Token])
+where
+    T: Serialize,
+{
+    let mut ser = Serializer::compact(tokens);
+    match value.serialize(&mut ser) {
+        Ok(_) => {}
+        Err(err) => panic!("value failed to serialize: {}", err),
+    }
+
+    if ser.remaining() > 0 {
+        panic!("{} remaining tokens", ser.remaining());
+    }
+}
+
 /// Asserts that `value` serializes to the given `tokens`, and then yields `error`.
 ///
 /// ```rust
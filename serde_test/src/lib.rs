@@ -168,8 +168,8 @@ mod token;
 mod assert;
 
 pub use token::Token;
-pub use assert::{assert_tokens, assert_ser_tokens, assert_ser_tokens_error, assert_de_tokens,
-                 assert_de_tokens_error};
+pub use assert::{assert_tokens, assert_ser_tokens, assert_ser_tokens_compact,
+                 assert_ser_tokens_error, assert_de_tokens, assert_de_tokens_error};
 
 // Not public API.
 #[doc(hidden)]
@@ -15,12 +15,24 @@ use token::Token;
 #[derive(Debug)]
 pub struct Serializer<'a> {
     tokens: &'a [Token],
+    human_readable: bool,
 }
 
 impl<'a> Serializer<'a> {
     /// Creates the serializer.
     pub fn new(tokens: &'a [Token]) -> Self {
-        Serializer { tokens: tokens }
+        Serializer {
+            tokens: tokens,
+            human_readable: true,
+        }
+    }
+
+    /// Creates a serializer that reports itself as not human readable.
+    pub fn compact(tokens: &'a [Token]) -> Self {
+        Serializer {
+            tokens: tokens,
+            human_readable: false,
+        }
     }
 
     /// Pulls the next token off of the serializer, ignoring it.
@@ -282,6 +294,10 @@ impl<'s, 'a> ser::Serializer for &'s mut Serializer<'a> {
             Ok(Variant { ser: self, end: Token::StructVariantEnd })
         }
     }
+
+    fn is_human_readable(&self) -> bool {
+        self.human_readable
+    }
 }
 
 pub struct Variant<'s, 'a: 's> {
@@ -0,0 +1,20 @@
+// Copyright 2017 Serde Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[macro_use]
+extern crate serde_derive;
+
+#[derive(Serialize)] //~ ERROR: proc-macro derive panicked
+#[serde(as_flags)] //~^ HELP: #[serde(as_flags)] cannot skip field `write` with #[serde(skip_serializing_if)]
+struct S {
+    read: bool,
+    #[serde(skip_serializing_if = "is_false")]
+    write: bool,
+}
+
+fn main() {}
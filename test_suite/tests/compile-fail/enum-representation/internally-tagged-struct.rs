@@ -10,7 +10,7 @@
 extern crate serde_derive;
 
 #[derive(Serialize)] //~ ERROR: proc-macro derive panicked
-#[serde(tag = "type")] //~^ HELP: #[serde(tag = "...")] can only be used on enums
+#[serde(tag = "type")] //~^ HELP: #[serde(tag = "...")] can only be used on enums and structs with named fields
 struct S;
 
 fn main() {}
@@ -0,0 +1,15 @@
+// Copyright 2017 Serde Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[macro_use]
+extern crate serde_derive;
+
+#[derive(Serialize)] //~ ERROR: proc-macro derive panicked
+struct S(u8, #[serde(flatten)] u16); //~^ HELP: #[serde(flatten)] cannot be used on field `1`, flattened fields of a tuple struct must serialize as a tuple or sequence
+
+fn main() {}
@@ -10,9 +10,10 @@
 extern crate serde_derive;
 
 #[derive(Deserialize)] //~ ERROR: proc-macro derive panicked
+#[serde(variant_identifier)]
 enum F {
     A,
-    #[serde(other)] //~^^^ HELP: #[serde(other)] may only be used inside a field_identifier
+    #[serde(other)] //~^^^^ HELP: #[serde(other)] may not be used inside a variant_identifier
     B,
 }
 
@@ -0,0 +1,19 @@
+// Copyright 2017 Serde Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[macro_use]
+extern crate serde_derive;
+
+#[derive(Serialize)] //~ ERROR: proc-macro derive panicked
+#[serde(rename_all = "camelCase", rename_all_skip = "id, url")] //~^ HELP: #[serde(rename_all_skip)] refers to unknown field or variant `url`
+struct S {
+    id: u8,
+    home_url: u8,
+}
+
+fn main() {}
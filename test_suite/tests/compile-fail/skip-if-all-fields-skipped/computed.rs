@@ -0,0 +1,19 @@
+// Copyright 2017 Serde Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[macro_use]
+extern crate serde_derive;
+
+#[derive(Serialize)] //~ ERROR: proc-macro derive panicked
+#[serde(skip_if_all_fields_skipped, computed(name = "c", value = "S::c"))] //~^ HELP: #[serde(skip_if_all_fields_skipped)] cannot be used together with #[serde(computed(...))]
+struct S {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    a: Option<u8>,
+}
+
+fn main() {}
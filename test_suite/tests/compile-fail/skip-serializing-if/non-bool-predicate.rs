@@ -0,0 +1,23 @@
+// Copyright 2017 Serde Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[macro_use]
+extern crate serde_derive;
+
+fn count(x: &u8) -> u8 {
+    *x
+}
+
+#[derive(Serialize)] //~ ERROR: mismatched types
+struct S(
+    #[serde(skip_serializing_if="count")]
+    u8,
+    u8,
+);
+
+fn main() {}
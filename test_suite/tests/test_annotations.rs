@@ -13,8 +13,8 @@ extern crate serde;
 use self::serde::{Serialize, Serializer, Deserialize, Deserializer};
 
 extern crate serde_test;
-use self::serde_test::{Token, assert_tokens, assert_ser_tokens, assert_de_tokens,
-                       assert_de_tokens_error};
+use self::serde_test::{Token, assert_tokens, assert_ser_tokens, assert_ser_tokens_compact,
+                       assert_de_tokens, assert_de_tokens_error, assert_ser_tokens_error};
 
 trait MyDefault: Sized {
     fn my_default() -> Self;
@@ -431,6 +431,39 @@ fn test_rename_struct() {
     );
 }
 
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct RenameDeserializeOnly {
+    #[serde(rename(deserialize="userId"))]
+    user_id: i32,
+}
+
+#[test]
+fn test_rename_deserialize_only() {
+    assert_ser_tokens(
+        &RenameDeserializeOnly { user_id: 1 },
+        &[
+            Token::Struct { name: "RenameDeserializeOnly", len: 1 },
+
+            Token::Str("user_id"),
+            Token::I32(1),
+
+            Token::StructEnd,
+        ],
+    );
+
+    assert_de_tokens(
+        &RenameDeserializeOnly { user_id: 1 },
+        &[
+            Token::Struct { name: "RenameDeserializeOnly", len: 1 },
+
+            Token::Str("userId"),
+            Token::I32(1),
+
+            Token::StructEnd,
+        ],
+    );
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename="Superhero")]
 enum RenameEnum {
@@ -533,6 +566,83 @@ fn test_rename_enum() {
     );
 }
 
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename="Villain", tag="type")]
+enum RenameInternallyTaggedEnum {
+    Joker,
+    Bane { strength: u8 },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename="Villain", tag="t", content="c")]
+enum RenameAdjacentlyTaggedEnum {
+    Joker,
+    Riddler(u8),
+    Bane { strength: u8 },
+}
+
+#[test]
+fn test_rename_tagged_enum() {
+    assert_tokens(
+        &RenameInternallyTaggedEnum::Joker,
+        &[
+            Token::Struct { name: "Villain", len: 1 },
+            Token::Str("type"),
+            Token::Str("Joker"),
+            Token::StructEnd,
+        ],
+    );
+
+    assert_tokens(
+        &RenameInternallyTaggedEnum::Bane { strength: 9 },
+        &[
+            Token::Struct { name: "Villain", len: 2 },
+            Token::Str("type"),
+            Token::Str("Bane"),
+            Token::Str("strength"),
+            Token::U8(9),
+            Token::StructEnd,
+        ],
+    );
+
+    assert_tokens(
+        &RenameAdjacentlyTaggedEnum::Joker,
+        &[
+            Token::Struct { name: "Villain", len: 1 },
+            Token::Str("t"),
+            Token::Str("Joker"),
+            Token::StructEnd,
+        ],
+    );
+
+    assert_tokens(
+        &RenameAdjacentlyTaggedEnum::Riddler(1),
+        &[
+            Token::Struct { name: "Villain", len: 2 },
+            Token::Str("t"),
+            Token::Str("Riddler"),
+            Token::Str("c"),
+            Token::U8(1),
+            Token::StructEnd,
+        ],
+    );
+
+    assert_tokens(
+        &RenameAdjacentlyTaggedEnum::Bane { strength: 9 },
+        &[
+            Token::Struct { name: "Villain", len: 2 },
+            Token::Str("t"),
+            Token::Str("Bane"),
+            Token::Str("c"),
+            Token::Struct { name: "Bane", len: 1 },
+            Token::Str("strength"),
+            Token::U8(9),
+            Token::StructEnd,
+            Token::StructEnd,
+        ],
+    );
+}
+
 #[derive(Debug, PartialEq, Serialize)]
 struct SkipSerializingStruct<'a, B, C>
 where
@@ -664,6 +774,65 @@ fn test_skip_serializing_enum() {
     );
 }
 
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum SkipAllFieldsEnum {
+    Struct {
+        #[serde(skip)]
+        a: i8,
+        #[serde(skip)]
+        b: i8,
+    },
+    Tuple(#[serde(skip)] i8, #[serde(skip)] i8),
+}
+
+#[test]
+fn test_skip_all_fields_enum() {
+    assert_ser_tokens(
+        &SkipAllFieldsEnum::Struct { a: 1, b: 2 },
+        &[Token::UnitVariant { name: "SkipAllFieldsEnum", variant: "Struct" }],
+    );
+
+    assert_de_tokens(
+        &SkipAllFieldsEnum::Struct { a: 0, b: 0 },
+        &[Token::UnitVariant { name: "SkipAllFieldsEnum", variant: "Struct" }],
+    );
+
+    assert_tokens(
+        &SkipAllFieldsEnum::Tuple(0, 0),
+        &[Token::UnitVariant { name: "SkipAllFieldsEnum", variant: "Tuple" }],
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum SkipSerializingAllFields {
+    Struct {
+        #[serde(skip_serializing, default)]
+        a: i8,
+    },
+}
+
+#[test]
+fn test_skip_serializing_all_fields_enum() {
+    // Still a struct variant in both directions, so the serialized form can
+    // be deserialized. serde_test checks the length against the number of
+    // fields the variant declares, which differs between the two.
+    assert_ser_tokens(
+        &SkipSerializingAllFields::Struct { a: 1 },
+        &[
+            Token::StructVariant { name: "SkipSerializingAllFields", variant: "Struct", len: 0 },
+            Token::StructVariantEnd,
+        ],
+    );
+
+    assert_de_tokens(
+        &SkipSerializingAllFields::Struct { a: 0 },
+        &[
+            Token::StructVariant { name: "SkipSerializingAllFields", variant: "Struct", len: 1 },
+            Token::StructVariantEnd,
+        ],
+    );
+}
+
 #[derive(Debug, PartialEq)]
 struct NotSerializeStruct(i8);
 
@@ -811,6 +980,49 @@ fn test_serialize_with_enum() {
     );
 }
 
+struct Timestamp(u32);
+
+fn serialize_timestamp<S>(timestamp: &Timestamp, ser: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    ser.serialize_u32(timestamp.0)
+}
+
+fn is_epoch(timestamp: &Timestamp) -> bool {
+    timestamp.0 == 0
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SerializeWithSkipRename {
+    #[serde(serialize_with = "serialize_timestamp", skip_serializing_if = "is_epoch")]
+    created_at: Timestamp,
+}
+
+#[test]
+fn test_serialize_with_skip_serializing_if_rename_all() {
+    assert_ser_tokens(
+        &SerializeWithSkipRename { created_at: Timestamp(7) },
+        &[
+            Token::Struct { name: "SerializeWithSkipRename", len: 1 },
+
+            Token::Str("createdAt"),
+            Token::U32(7),
+
+            Token::StructEnd,
+        ],
+    );
+
+    assert_ser_tokens(
+        &SerializeWithSkipRename { created_at: Timestamp(0) },
+        &[
+            Token::Struct { name: "SerializeWithSkipRename", len: 0 },
+            Token::StructEnd,
+        ],
+    );
+}
+
 #[derive(Debug, PartialEq, Deserialize)]
 struct DeserializeWithStruct<B>
 where
@@ -899,6 +1111,54 @@ fn test_deserialize_with_enum() {
     );
 }
 
+fn deserialize_hex<'de, D>(de: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    use serde::de::Error;
+    let s = try!(String::deserialize(de));
+    u64::from_str_radix(&s, 16).map_err(|_| D::Error::custom(format!("invalid hex `{}`", s)))
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct DeserializeWithDefault {
+    #[serde(deserialize_with = "deserialize_hex", default)]
+    id: u64,
+}
+
+#[test]
+fn test_deserialize_with_default() {
+    assert_de_tokens(
+        &DeserializeWithDefault { id: 0xff },
+        &[
+            Token::Struct { name: "DeserializeWithDefault", len: 1 },
+
+            Token::Str("id"),
+            Token::Str("ff"),
+
+            Token::StructEnd,
+        ],
+    );
+
+    assert_de_tokens(
+        &DeserializeWithDefault { id: 0 },
+        &[
+            Token::Struct { name: "DeserializeWithDefault", len: 0 },
+            Token::StructEnd,
+        ],
+    );
+
+    assert_de_tokens_error::<DeserializeWithDefault>(
+        &[
+            Token::Struct { name: "DeserializeWithDefault", len: 1 },
+
+            Token::Str("id"),
+            Token::Str("xyz"),
+        ],
+        "invalid hex `xyz`",
+    );
+}
+
 #[test]
 fn test_missing_renamed_field_struct() {
     assert_de_tokens_error::<RenameStruct>(
@@ -1042,3 +1302,2095 @@ fn test_from_into_traits() {
     assert_ser_tokens::<StructFromEnum>(&StructFromEnum(None), &[Token::None]);
     assert_de_tokens::<StructFromEnum>(&StructFromEnum(Some(2)), &[Token::Some, Token::U32(2)]);
 }
+
+#[derive(Serialize)]
+#[serde(partial)]
+struct PartialStruct {
+    a: i32,
+    #[serde(rename="c")]
+    b: i32,
+    #[serde(skip_serializing_if="Option::is_none")]
+    d: Option<i32>,
+}
+
+struct SerializeFields<'a>(&'a PartialStruct, &'a [&'a str]);
+
+impl<'a> Serialize for SerializeFields<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize_fields(serializer, self.1)
+    }
+}
+
+#[test]
+fn test_partial_struct() {
+    let value = PartialStruct { a: 1, b: 2, d: None };
+
+    assert_ser_tokens(
+        &value,
+        &[
+            Token::Struct { name: "PartialStruct", len: 2 },
+
+            Token::Str("a"),
+            Token::I32(1),
+
+            Token::Str("c"),
+            Token::I32(2),
+
+            Token::StructEnd,
+        ],
+    );
+
+    assert_ser_tokens(
+        &SerializeFields(&value, &["c", "d", "unknown"]),
+        &[
+            Token::Struct { name: "PartialStruct", len: 1 },
+
+            Token::Str("c"),
+            Token::I32(2),
+
+            Token::StructEnd,
+        ],
+    );
+
+    assert_ser_tokens(
+        &SerializeFields(&PartialStruct { a: 1, b: 2, d: Some(3) }, &["a", "d"]),
+        &[
+            Token::Struct { name: "PartialStruct", len: 2 },
+
+            Token::Str("a"),
+            Token::I32(1),
+
+            Token::Str("d"),
+            Token::Some,
+            Token::I32(3),
+
+            Token::StructEnd,
+        ],
+    );
+}
+
+const RENAMED_FIELD: &'static str = "renamed";
+
+#[derive(Debug, PartialEq, Serialize)]
+struct RenameConstStruct {
+    #[serde(rename_const="RENAMED_FIELD")]
+    a1: i32,
+    a2: i32,
+}
+
+#[test]
+fn test_rename_const_struct() {
+    assert_ser_tokens(
+        &RenameConstStruct { a1: 1, a2: 2 },
+        &[
+            Token::Struct { name: "RenameConstStruct", len: 2 },
+
+            Token::Str("renamed"),
+            Token::I32(1),
+
+            Token::Str("a2"),
+            Token::I32(2),
+
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(as_flags)]
+struct Permissions {
+    read: bool,
+    #[serde(rename="write")]
+    modify: bool,
+    execute: bool,
+    #[serde(skip_serializing)]
+    #[allow(dead_code)]
+    cached: Option<u8>,
+}
+
+#[test]
+fn test_as_flags() {
+    assert_ser_tokens(
+        &Permissions {
+             read: true,
+             modify: true,
+             execute: false,
+             cached: None,
+         },
+        &[
+            Token::Seq { len: Some(2) },
+            Token::Str("read"),
+            Token::Str("write"),
+            Token::SeqEnd,
+        ],
+    );
+
+    assert_ser_tokens(
+        &Permissions {
+             read: false,
+             modify: false,
+             execute: false,
+             cached: Some(1),
+         },
+        &[Token::Seq { len: Some(0) }, Token::SeqEnd],
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct SkipSerializingIfClosure {
+    #[serde(skip_serializing_if="|v| v.len() > 2")]
+    short: Vec<i32>,
+    #[serde(skip_serializing_if="|n| *n == 0")]
+    nonzero: i32,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+enum SkipSerializingIfClosureEnum {
+    Struct {
+        #[serde(skip_serializing_if="|n| *n == 0")]
+        nonzero: i32,
+    },
+}
+
+#[test]
+fn test_skip_serializing_if_closure() {
+    assert_ser_tokens(
+        &SkipSerializingIfClosure {
+             short: vec![1, 2],
+             nonzero: 3,
+         },
+        &[
+            Token::Struct { name: "SkipSerializingIfClosure", len: 2 },
+
+            Token::Str("short"),
+            Token::Seq { len: Some(2) },
+            Token::I32(1),
+            Token::I32(2),
+            Token::SeqEnd,
+
+            Token::Str("nonzero"),
+            Token::I32(3),
+
+            Token::StructEnd,
+        ],
+    );
+
+    assert_ser_tokens(
+        &SkipSerializingIfClosure {
+             short: vec![1, 2, 3],
+             nonzero: 0,
+         },
+        &[Token::Struct { name: "SkipSerializingIfClosure", len: 0 }, Token::StructEnd],
+    );
+
+    assert_ser_tokens(
+        &SkipSerializingIfClosureEnum::Struct { nonzero: 0 },
+        &[
+            Token::StructVariant { name: "SkipSerializingIfClosureEnum", variant: "Struct", len: 0 },
+            Token::StructVariantEnd,
+        ],
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(intern_keys)]
+struct InternKeysStruct<T> {
+    a1: T,
+    #[serde(skip_serializing)]
+    a2: T,
+    #[serde(rename_const="RENAMED_FIELD")]
+    a3: T,
+}
+
+#[test]
+fn test_intern_keys() {
+    assert_ser_tokens(
+        &InternKeysStruct { a1: 1, a2: 2, a3: 3 },
+        &[
+            Token::Struct { name: "InternKeysStruct", len: 2 },
+
+            Token::Str("a1"),
+            Token::I32(1),
+
+            Token::Str("renamed"),
+            Token::I32(3),
+
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(computed(name = "full", value = "ComputedStruct::full"))]
+struct ComputedStruct {
+    first: &'static str,
+    last: &'static str,
+}
+
+impl ComputedStruct {
+    fn full(&self) -> String {
+        format!("{} {}", self.first, self.last)
+    }
+}
+
+#[test]
+fn test_computed() {
+    assert_ser_tokens(
+        &ComputedStruct { first: "Ada", last: "Lovelace" },
+        &[
+            Token::Struct { name: "ComputedStruct", len: 3 },
+
+            Token::Str("first"),
+            Token::Str("Ada"),
+
+            Token::Str("last"),
+            Token::Str("Lovelace"),
+
+            Token::Str("full"),
+            Token::Str("Ada Lovelace"),
+
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct RenameChainStruct {
+    #[serde(rename("display_name", "displayName", "name"))]
+    display_name: String,
+    age: u8,
+}
+
+#[test]
+fn test_rename_chain() {
+    assert_tokens(
+        &RenameChainStruct { display_name: "Bob".to_owned(), age: 3 },
+        &[
+            Token::Struct { name: "RenameChainStruct", len: 2 },
+
+            Token::Str("display_name"),
+            Token::Str("Bob"),
+
+            Token::Str("age"),
+            Token::U8(3),
+
+            Token::StructEnd,
+        ],
+    );
+
+    assert_de_tokens(
+        &RenameChainStruct { display_name: "Bob".to_owned(), age: 3 },
+        &[
+            Token::Struct { name: "RenameChainStruct", len: 2 },
+
+            Token::Str("displayName"),
+            Token::Str("Bob"),
+
+            Token::Str("age"),
+            Token::U8(3),
+
+            Token::StructEnd,
+        ],
+    );
+
+    assert_de_tokens(
+        &RenameChainStruct { display_name: "Bob".to_owned(), age: 3 },
+        &[
+            Token::Struct { name: "RenameChainStruct", len: 2 },
+
+            Token::Str("name"),
+            Token::Str("Bob"),
+
+            Token::Str("age"),
+            Token::U8(3),
+
+            Token::StructEnd,
+        ],
+    );
+
+    assert_de_tokens_error::<RenameChainStruct>(
+        &[
+            Token::Struct { name: "RenameChainStruct", len: 2 },
+
+            Token::Str("age"),
+            Token::U8(3),
+
+            Token::StructEnd,
+        ],
+        "missing field `display_name`",
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct FlattenOptionStruct {
+    id: u32,
+    #[serde(flatten)]
+    extra: Option<FlattenExtra>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct FlattenExtra {
+    a: u8,
+    b: u8,
+}
+
+#[test]
+fn test_flatten_option() {
+    assert_ser_tokens(
+        &FlattenOptionStruct {
+             id: 1,
+             extra: Some(FlattenExtra { a: 2, b: 3 }),
+         },
+        &[
+            Token::Map { len: None },
+
+            Token::Str("id"),
+            Token::U32(1),
+
+            Token::Str("a"),
+            Token::U8(2),
+
+            Token::Str("b"),
+            Token::U8(3),
+
+            Token::MapEnd,
+        ],
+    );
+
+    assert_ser_tokens(
+        &FlattenOptionStruct { id: 1, extra: None },
+        &[
+            Token::Map { len: None },
+
+            Token::Str("id"),
+            Token::U32(1),
+
+            Token::MapEnd,
+        ],
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tuple_names = "x, y, z")]
+struct TupleNamesStruct(i32, i32, i32);
+
+#[test]
+fn test_tuple_names() {
+    assert_ser_tokens(
+        &TupleNamesStruct(1, 2, 3),
+        &[
+            Token::Map { len: Some(3) },
+
+            Token::Str("x"),
+            Token::I32(1),
+
+            Token::Str("y"),
+            Token::I32(2),
+
+            Token::Str("z"),
+            Token::I32(3),
+
+            Token::MapEnd,
+        ],
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct FlattenOrderV1 {
+    id: u8,
+    #[serde(flatten)]
+    first: FlattenExtra,
+    name: u8,
+    #[serde(flatten)]
+    second: FlattenMore,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct FlattenOrderV2 {
+    id: u8,
+    added: u8,
+    #[serde(flatten)]
+    first: FlattenExtra,
+    name: u8,
+    #[serde(flatten)]
+    second: FlattenMore,
+    appended: u8,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct FlattenMore {
+    c: u8,
+    d: u8,
+}
+
+#[test]
+fn test_flatten_order() {
+    assert_ser_tokens(
+        &FlattenOrderV1 {
+            id: 1,
+            first: FlattenExtra { a: 2, b: 3 },
+            name: 4,
+            second: FlattenMore { c: 5, d: 6 },
+        },
+        &[
+            Token::Map { len: None },
+
+            Token::Str("id"),
+            Token::U8(1),
+
+            Token::Str("a"),
+            Token::U8(2),
+
+            Token::Str("b"),
+            Token::U8(3),
+
+            Token::Str("name"),
+            Token::U8(4),
+
+            Token::Str("c"),
+            Token::U8(5),
+
+            Token::Str("d"),
+            Token::U8(6),
+
+            Token::MapEnd,
+        ],
+    );
+
+    assert_ser_tokens(
+        &FlattenOrderV2 {
+            id: 1,
+            added: 0,
+            first: FlattenExtra { a: 2, b: 3 },
+            name: 4,
+            second: FlattenMore { c: 5, d: 6 },
+            appended: 7,
+        },
+        &[
+            Token::Map { len: None },
+
+            Token::Str("id"),
+            Token::U8(1),
+
+            Token::Str("added"),
+            Token::U8(0),
+
+            Token::Str("a"),
+            Token::U8(2),
+
+            Token::Str("b"),
+            Token::U8(3),
+
+            Token::Str("name"),
+            Token::U8(4),
+
+            Token::Str("c"),
+            Token::U8(5),
+
+            Token::Str("d"),
+            Token::U8(6),
+
+            Token::Str("appended"),
+            Token::U8(7),
+
+            Token::MapEnd,
+        ],
+    );
+}
+
+type FlattenAlias = Vec<u8>;
+
+#[derive(Debug, PartialEq, Serialize)]
+struct FlattenSeqStruct {
+    #[serde(flatten)]
+    seq: FlattenAlias,
+}
+
+#[test]
+fn test_flatten_non_map() {
+    assert_ser_tokens_error(
+        &FlattenSeqStruct { seq: vec![1] },
+        &[Token::Map { len: None }],
+        "can only flatten structs and maps (got a sequence)",
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct FlattenTupleStruct(u8, #[serde(flatten)] (u16, u32), #[serde(flatten)] Vec<u8>);
+
+#[test]
+fn test_flatten_tuple_struct() {
+    assert_ser_tokens(
+        &FlattenTupleStruct(1, (2, 3), vec![4, 5]),
+        &[
+            Token::Seq { len: None },
+            Token::U8(1),
+            Token::U16(2),
+            Token::U32(3),
+            Token::U8(4),
+            Token::U8(5),
+            Token::SeqEnd,
+        ],
+    );
+}
+
+type FlattenMapAlias = ::std::collections::BTreeMap<u8, u8>;
+
+#[derive(Debug, PartialEq, Serialize)]
+struct FlattenMapTupleStruct(u8, #[serde(flatten)] FlattenMapAlias);
+
+#[test]
+fn test_flatten_tuple_struct_non_seq() {
+    assert_ser_tokens_error(
+        &FlattenMapTupleStruct(1, FlattenMapAlias::new()),
+        &[Token::Seq { len: None }, Token::U8(1)],
+        "can only flatten tuples and sequences (got a map)",
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+enum FlattenVariantEnum {
+    Struct {
+        id: u32,
+        #[serde(flatten)]
+        extra: FlattenExtra,
+    },
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "type")]
+enum FlattenVariantInternallyTagged {
+    Struct {
+        id: u32,
+        #[serde(flatten)]
+        extra: FlattenExtra,
+    },
+}
+
+#[test]
+fn test_flatten_struct_variant() {
+    assert_ser_tokens(
+        &FlattenVariantEnum::Struct { id: 1, extra: FlattenExtra { a: 2, b: 3 } },
+        &[
+            Token::Map { len: Some(1) },
+            Token::Str("Struct"),
+
+            Token::Map { len: None },
+            Token::Str("id"),
+            Token::U32(1),
+            Token::Str("a"),
+            Token::U8(2),
+            Token::Str("b"),
+            Token::U8(3),
+            Token::MapEnd,
+
+            Token::MapEnd,
+        ],
+    );
+
+    assert_ser_tokens(
+        &FlattenVariantInternallyTagged::Struct { id: 1, extra: FlattenExtra { a: 2, b: 3 } },
+        &[
+            Token::Map { len: None },
+            Token::Str("type"),
+            Token::Str("Struct"),
+            Token::Str("id"),
+            Token::U32(1),
+            Token::Str("a"),
+            Token::U8(2),
+            Token::Str("b"),
+            Token::U8(3),
+            Token::MapEnd,
+        ],
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct SkipSerializingIfGeneric<T> {
+    #[serde(skip_serializing_if = "T::should_skip", bound(serialize = "T: Serialize + ShouldSkip"))]
+    a: T,
+    #[serde(skip_serializing_if = "ShouldSkip::should_skip")]
+    #[serde(bound(serialize = "T: Serialize + ShouldSkip"))]
+    b: T,
+}
+
+#[test]
+fn test_skip_serializing_if_generic() {
+    assert_ser_tokens(
+        &SkipSerializingIfGeneric { a: 1, b: 2 },
+        &[
+            Token::Struct { name: "SkipSerializingIfGeneric", len: 2 },
+
+            Token::Str("a"),
+            Token::I32(1),
+
+            Token::Str("b"),
+            Token::I32(2),
+
+            Token::StructEnd,
+        ],
+    );
+
+    assert_ser_tokens(
+        &SkipSerializingIfGeneric { a: 123, b: 123 },
+        &[Token::Struct { name: "SkipSerializingIfGeneric", len: 0 }, Token::StructEnd],
+    );
+}
+
+#[derive(Serialize)]
+#[serde(emit_field_info)]
+struct FieldInfoStruct<'a, T> {
+    id: u32,
+    #[serde(rename = "tags")]
+    labels: Vec<&'a str>,
+    value: Option<(T, [u8; 4])>,
+    #[serde(skip_serializing)]
+    #[allow(dead_code)]
+    cache: u8,
+}
+
+#[test]
+fn test_emit_field_info() {
+    assert_eq!(
+        FieldInfoStruct::<'static, i32>::FIELDS,
+        &[
+            ("id", "u32"),
+            ("tags", "Vec<&'a str>"),
+            ("value", "Option<(T, [u8; 4])>"),
+        ]
+    );
+}
+
+#[derive(Serialize, Deserialize)]
+struct DescribedStruct {
+    #[serde(rename = "ID", description = "Unique identifier")]
+    id: u32,
+    undocumented: u8,
+    #[serde(description = "Never serialized", skip_serializing)]
+    cached: u8,
+    #[serde(description = "Display name, may contain \"quotes\"")]
+    name: String,
+}
+
+#[test]
+fn test_field_docs() {
+    assert_eq!(
+        DescribedStruct::FIELD_DOCS,
+        &[
+            ("ID", "Unique identifier"),
+            ("name", "Display name, may contain \"quotes\""),
+        ]
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum AsSeqEnum {
+    #[serde(as_seq)]
+    Point {
+        x: i32,
+        #[serde(skip)]
+        cached: u8,
+        y: i32,
+    },
+}
+
+#[test]
+fn test_as_seq_struct_variant() {
+    assert_tokens(
+        &AsSeqEnum::Point { x: 1, cached: 0, y: 2 },
+        &[
+            Token::TupleVariant { name: "AsSeqEnum", variant: "Point", len: 2 },
+            Token::I32(1),
+            Token::I32(2),
+            Token::TupleVariantEnd,
+        ],
+    );
+}
+
+fn is_compact<S: Serializer>(serializer: &S) -> bool {
+    !serializer.is_human_readable()
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct SkipUnlessSerializerStruct {
+    a: i8,
+    #[serde(skip_serializing_unless_serializer = "Serializer::is_human_readable")]
+    readable: i8,
+    #[serde(skip_serializing_unless_serializer = "is_compact")]
+    compact: i8,
+    #[serde(skip_serializing_unless_serializer = "Serializer::is_human_readable")]
+    #[serde(skip_serializing_if = "ShouldSkip::should_skip")]
+    both: i32,
+}
+
+#[test]
+fn test_skip_serializing_unless_serializer() {
+    assert_ser_tokens(
+        &SkipUnlessSerializerStruct { a: 1, readable: 2, compact: 3, both: 4 },
+        &[
+            Token::Struct { name: "SkipUnlessSerializerStruct", len: 3 },
+
+            Token::Str("a"),
+            Token::I8(1),
+
+            Token::Str("readable"),
+            Token::I8(2),
+
+            Token::Str("both"),
+            Token::I32(4),
+
+            Token::StructEnd,
+        ],
+    );
+
+    assert_ser_tokens(
+        &SkipUnlessSerializerStruct { a: 1, readable: 2, compact: 3, both: 123 },
+        &[
+            Token::Struct { name: "SkipUnlessSerializerStruct", len: 2 },
+
+            Token::Str("a"),
+            Token::I8(1),
+
+            Token::Str("readable"),
+            Token::I8(2),
+
+            Token::StructEnd,
+        ],
+    );
+}
+
+thread_local! {
+    static PREDICATE_CALLS: ::std::cell::Cell<usize> = ::std::cell::Cell::new(0);
+}
+
+fn counted_is_zero(n: &u8) -> bool {
+    PREDICATE_CALLS.with(|calls| calls.set(calls.get() + 1));
+    *n == 0
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct CountedSkipStruct {
+    #[serde(skip_serializing_if = "counted_is_zero")]
+    a: u8,
+    #[serde(skip_serializing_if = "counted_is_zero")]
+    b: u8,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+enum CountedSkipEnum {
+    Struct {
+        #[serde(skip_serializing_if = "counted_is_zero")]
+        a: u8,
+    },
+}
+
+#[test]
+fn test_skip_serializing_if_evaluated_once() {
+    PREDICATE_CALLS.with(|calls| calls.set(0));
+    assert_ser_tokens(
+        &CountedSkipStruct { a: 0, b: 1 },
+        &[
+            Token::Struct { name: "CountedSkipStruct", len: 1 },
+
+            Token::Str("b"),
+            Token::U8(1),
+
+            Token::StructEnd,
+        ],
+    );
+    assert_eq!(PREDICATE_CALLS.with(|calls| calls.get()), 2);
+
+    PREDICATE_CALLS.with(|calls| calls.set(0));
+    assert_ser_tokens(
+        &CountedSkipEnum::Struct { a: 1 },
+        &[
+            Token::StructVariant { name: "CountedSkipEnum", variant: "Struct", len: 1 },
+
+            Token::Str("a"),
+            Token::U8(1),
+
+            Token::StructVariantEnd,
+        ],
+    );
+    assert_eq!(PREDICATE_CALLS.with(|calls| calls.get()), 1);
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum ForwardCompatible {
+    Known,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+enum ForwardCompatibleTagged {
+    Known { a: u8 },
+    #[serde(other)]
+    Unknown,
+}
+
+#[test]
+fn test_other_variant() {
+    assert_tokens(
+        &ForwardCompatible::Unknown,
+        &[Token::UnitVariant { name: "ForwardCompatible", variant: "Unknown" }],
+    );
+    assert_de_tokens(
+        &ForwardCompatible::Unknown,
+        &[Token::UnitVariant { name: "ForwardCompatible", variant: "Added" }],
+    );
+    assert_de_tokens(
+        &ForwardCompatible::Known,
+        &[Token::UnitVariant { name: "ForwardCompatible", variant: "Known" }],
+    );
+
+    assert_tokens(
+        &ForwardCompatibleTagged::Unknown,
+        &[
+            Token::Struct { name: "ForwardCompatibleTagged", len: 1 },
+
+            Token::Str("type"),
+            Token::Str("Unknown"),
+
+            Token::StructEnd,
+        ],
+    );
+    assert_de_tokens(
+        &ForwardCompatibleTagged::Unknown,
+        &[
+            Token::Map { len: Some(1) },
+
+            Token::Str("type"),
+            Token::Str("Added"),
+
+            Token::MapEnd,
+        ],
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct FloatPrecisionStruct {
+    #[serde(float_precision = 2)]
+    a: f64,
+    #[serde(float_precision = 0)]
+    b: f32,
+}
+
+#[test]
+fn test_float_precision() {
+    assert_ser_tokens(
+        &FloatPrecisionStruct { a: 3.14159, b: 2.5 },
+        &[
+            Token::Struct { name: "FloatPrecisionStruct", len: 2 },
+
+            Token::Str("a"),
+            Token::Str("3.14"),
+
+            Token::Str("b"),
+            Token::Str("2"),
+
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(scalar_field = "id")]
+struct ScalarFieldStruct {
+    id: u32,
+    cached_label: String,
+}
+
+#[test]
+fn test_scalar_field() {
+    assert_ser_tokens(
+        &ScalarFieldStruct { id: 7, cached_label: "seven".to_owned() },
+        &[Token::U32(7)],
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct RenameByReadabilityStruct {
+    #[serde(rename_hr = "identifier", rename_compact = "i")]
+    id: u32,
+    #[serde(rename_compact = "n")]
+    name: &'static str,
+}
+
+#[test]
+fn test_rename_by_readability() {
+    let value = RenameByReadabilityStruct { id: 1, name: "one" };
+
+    assert_ser_tokens(
+        &value,
+        &[
+            Token::Struct { name: "RenameByReadabilityStruct", len: 2 },
+
+            Token::Str("identifier"),
+            Token::U32(1),
+
+            Token::Str("name"),
+            Token::Str("one"),
+
+            Token::StructEnd,
+        ],
+    );
+
+    assert_ser_tokens_compact(
+        &value,
+        &[
+            Token::Struct { name: "RenameByReadabilityStruct", len: 2 },
+
+            Token::Str("i"),
+            Token::U32(1),
+
+            Token::Str("n"),
+            Token::Str("one"),
+
+            Token::StructEnd,
+        ],
+    );
+
+    assert_de_tokens(
+        &value,
+        &[
+            Token::Struct { name: "RenameByReadabilityStruct", len: 2 },
+
+            Token::Str("identifier"),
+            Token::U32(1),
+
+            Token::Str("n"),
+            Token::BorrowedStr("one"),
+
+            Token::StructEnd,
+        ],
+    );
+
+    assert_de_tokens(
+        &value,
+        &[
+            Token::Struct { name: "RenameByReadabilityStruct", len: 2 },
+
+            Token::Str("i"),
+            Token::U32(1),
+
+            Token::Str("name"),
+            Token::BorrowedStr("one"),
+
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[derive(Serialize)]
+struct FlattenRenameByReadability {
+    version: u8,
+    #[serde(flatten)]
+    inner: RenameByReadabilityStruct,
+}
+
+#[test]
+fn test_flatten_rename_by_readability() {
+    let value = FlattenRenameByReadability {
+        version: 2,
+        inner: RenameByReadabilityStruct { id: 1, name: "one" },
+    };
+
+    assert_ser_tokens(
+        &value,
+        &[
+            Token::Map { len: None },
+
+            Token::Str("version"),
+            Token::U8(2),
+
+            Token::Str("identifier"),
+            Token::U32(1),
+
+            Token::Str("name"),
+            Token::Str("one"),
+
+            Token::MapEnd,
+        ],
+    );
+
+    assert_ser_tokens_compact(
+        &value,
+        &[
+            Token::Map { len: None },
+
+            Token::Str("version"),
+            Token::U8(2),
+
+            Token::Str("i"),
+            Token::U32(1),
+
+            Token::Str("n"),
+            Token::Str("one"),
+
+            Token::MapEnd,
+        ],
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct EmptyCollectionStruct {
+    kept: Vec<u8>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    skipped: Vec<u8>,
+}
+
+#[test]
+fn test_empty_collection_not_skipped_by_default() {
+    assert_ser_tokens(
+        &EmptyCollectionStruct { kept: Vec::new(), skipped: Vec::new() },
+        &[
+            Token::Struct { name: "EmptyCollectionStruct", len: 1 },
+
+            Token::Str("kept"),
+            Token::Seq { len: Some(0) },
+            Token::SeqEnd,
+
+            Token::StructEnd,
+        ],
+    );
+}
+
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct SkipDefaultStruct {
+    #[serde(skip_serializing_if = "is_default")]
+    a: u8,
+    #[serde(skip_serializing_if = "is_default")]
+    b: String,
+    #[serde(skip_serializing_if = "is_default")]
+    c: Option<bool>,
+}
+
+#[test]
+fn test_skip_serializing_if_generic_fn() {
+    assert_ser_tokens(
+        &SkipDefaultStruct { a: 0, b: String::new(), c: None },
+        &[
+            Token::Struct { name: "SkipDefaultStruct", len: 0 },
+            Token::StructEnd,
+        ],
+    );
+
+    assert_ser_tokens(
+        &SkipDefaultStruct { a: 1, b: String::new(), c: Some(false) },
+        &[
+            Token::Struct { name: "SkipDefaultStruct", len: 2 },
+
+            Token::Str("a"),
+            Token::U8(1),
+
+            Token::Str("c"),
+            Token::Some,
+            Token::Bool(false),
+
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(external_as_map)]
+enum ExternalAsMapEnum {
+    Unit,
+    Newtype(u8),
+    Tuple(u8, u8),
+    Struct { a: u8 },
+}
+
+#[test]
+fn test_external_as_map() {
+    assert_ser_tokens(
+        &ExternalAsMapEnum::Unit,
+        &[
+            Token::Map { len: Some(1) },
+
+            Token::Str("Unit"),
+            Token::Unit,
+
+            Token::MapEnd,
+        ],
+    );
+
+    assert_ser_tokens(
+        &ExternalAsMapEnum::Newtype(1),
+        &[
+            Token::Map { len: Some(1) },
+
+            Token::Str("Newtype"),
+            Token::U8(1),
+
+            Token::MapEnd,
+        ],
+    );
+
+    assert_ser_tokens(
+        &ExternalAsMapEnum::Tuple(1, 2),
+        &[
+            Token::Map { len: Some(1) },
+
+            Token::Str("Tuple"),
+            Token::Tuple { len: 2 },
+            Token::U8(1),
+            Token::U8(2),
+            Token::TupleEnd,
+
+            Token::MapEnd,
+        ],
+    );
+
+    assert_ser_tokens(
+        &ExternalAsMapEnum::Struct { a: 1 },
+        &[
+            Token::Map { len: Some(1) },
+
+            Token::Str("Struct"),
+            Token::Struct { name: "Struct", len: 1 },
+            Token::Str("a"),
+            Token::U8(1),
+            Token::StructEnd,
+
+            Token::MapEnd,
+        ],
+    );
+}
+
+// Computes its elements on demand instead of storing them.
+#[derive(Debug, PartialEq)]
+struct Evens {
+    count: u32,
+}
+
+impl<'a> IntoIterator for &'a Evens {
+    type Item = u32;
+    type IntoIter = ::std::iter::Map<::std::ops::Range<u32>, fn(u32) -> u32>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        fn double(i: u32) -> u32 {
+            i * 2
+        }
+        (0..self.count).map(double as fn(u32) -> u32)
+    }
+}
+
+fn serialize_iter<'a, T, S>(value: &'a T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    &'a T: IntoIterator,
+    <&'a T as IntoIterator>::Item: Serialize,
+    S: Serializer,
+{
+    serializer.collect_seq(value)
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct StreamingStruct {
+    #[serde(serialize_with = "serialize_iter")]
+    evens: Evens,
+}
+
+#[test]
+fn test_serialize_with_streaming_iterator() {
+    assert_ser_tokens(
+        &StreamingStruct { evens: Evens { count: 3 } },
+        &[
+            Token::Struct { name: "StreamingStruct", len: 1 },
+
+            Token::Str("evens"),
+            Token::Seq { len: Some(3) },
+            Token::U32(0),
+            Token::U32(2),
+            Token::U32(4),
+            Token::SeqEnd,
+
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+enum TransparentEnum {
+    Only(u32),
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+struct TransparentStruct {
+    inner: u32,
+}
+
+#[test]
+fn test_transparent() {
+    assert_tokens(&TransparentEnum::Only(1), &[Token::U32(1)]);
+    assert_tokens(&TransparentStruct { inner: 1 }, &[Token::U32(1)]);
+}
+
+// Takes a mutable borrow so that serialization panics if the borrow is still
+// held when the field is serialized.
+fn cell_is_empty(cell: &::std::cell::RefCell<Vec<u8>>) -> bool {
+    cell.borrow_mut().is_empty()
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct RefCellSkipStruct {
+    #[serde(skip_serializing_if = "cell_is_empty")]
+    cell: ::std::cell::RefCell<Vec<u8>>,
+}
+
+#[test]
+fn test_skip_serializing_if_releases_borrow() {
+    assert_ser_tokens(
+        &RefCellSkipStruct { cell: ::std::cell::RefCell::new(vec![1]) },
+        &[
+            Token::Struct { name: "RefCellSkipStruct", len: 1 },
+
+            Token::Str("cell"),
+            Token::Seq { len: Some(1) },
+            Token::U8(1),
+            Token::SeqEnd,
+
+            Token::StructEnd,
+        ],
+    );
+
+    assert_ser_tokens(
+        &RefCellSkipStruct { cell: ::std::cell::RefCell::new(Vec::new()) },
+        &[
+            Token::Struct { name: "RefCellSkipStruct", len: 0 },
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Int128AsStringStruct {
+    #[serde(int128_as_string)]
+    unsigned: u128,
+    #[serde(int128_as_string)]
+    signed: i128,
+}
+
+#[test]
+fn test_int128_as_string() {
+    assert_ser_tokens(
+        &Int128AsStringStruct {
+            unsigned: 340282366920938463463374607431768211455,
+            signed: -170141183460469231731687303715884105728,
+        },
+        &[
+            Token::Struct { name: "Int128AsStringStruct", len: 2 },
+
+            Token::Str("unsigned"),
+            Token::Str("340282366920938463463374607431768211455"),
+
+            Token::Str("signed"),
+            Token::Str("-170141183460469231731687303715884105728"),
+
+            Token::StructEnd,
+        ],
+    );
+}
+
+thread_local! {
+    static FIELDS_SERIALIZED: ::std::cell::Cell<usize> = ::std::cell::Cell::new(0);
+}
+
+struct CountFields<S>(S);
+
+fn count_fields<S: Serializer>(ser: S) -> CountFields<S> {
+    CountFields(ser)
+}
+
+macro_rules! forward_to_inner {
+    ($($method:ident($($arg:ident: $ty:ty),*) -> $ret:ty;)*) => {
+        $(
+            fn $method(self, $($arg: $ty),*) -> Result<$ret, S::Error> {
+                self.0.$method($($arg),*)
+            }
+        )*
+    };
+}
+
+impl<S: Serializer> Serializer for CountFields<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = S::SerializeSeq;
+    type SerializeTuple = S::SerializeTuple;
+    type SerializeTupleStruct = S::SerializeTupleStruct;
+    type SerializeTupleVariant = S::SerializeTupleVariant;
+    type SerializeMap = S::SerializeMap;
+    type SerializeStruct = CountFields<S::SerializeStruct>;
+    type SerializeStructVariant = S::SerializeStructVariant;
+
+    forward_to_inner! {
+        serialize_bool(v: bool) -> S::Ok;
+        serialize_i8(v: i8) -> S::Ok;
+        serialize_i16(v: i16) -> S::Ok;
+        serialize_i32(v: i32) -> S::Ok;
+        serialize_i64(v: i64) -> S::Ok;
+        serialize_u8(v: u8) -> S::Ok;
+        serialize_u16(v: u16) -> S::Ok;
+        serialize_u32(v: u32) -> S::Ok;
+        serialize_u64(v: u64) -> S::Ok;
+        serialize_f32(v: f32) -> S::Ok;
+        serialize_f64(v: f64) -> S::Ok;
+        serialize_char(v: char) -> S::Ok;
+        serialize_str(v: &str) -> S::Ok;
+        serialize_bytes(v: &[u8]) -> S::Ok;
+        serialize_none() -> S::Ok;
+        serialize_unit() -> S::Ok;
+        serialize_unit_struct(name: &'static str) -> S::Ok;
+        serialize_unit_variant(name: &'static str, index: u32, variant: &'static str) -> S::Ok;
+        serialize_seq(len: Option<usize>) -> S::SerializeSeq;
+        serialize_tuple(len: usize) -> S::SerializeTuple;
+        serialize_tuple_struct(name: &'static str, len: usize) -> S::SerializeTupleStruct;
+        serialize_tuple_variant(name: &'static str, index: u32, variant: &'static str, len: usize) -> S::SerializeTupleVariant;
+        serialize_map(len: Option<usize>) -> S::SerializeMap;
+        serialize_struct_variant(name: &'static str, index: u32, variant: &'static str, len: usize) -> S::SerializeStructVariant;
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<S::Ok, S::Error> {
+        self.0.serialize_some(value)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        self.0.serialize_newtype_struct(name, value)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        self.0.serialize_newtype_variant(name, index, variant, value)
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, S::Error> {
+        self.0.serialize_struct(name, len).map(CountFields)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.0.is_human_readable()
+    }
+}
+
+impl<S: serde::ser::SerializeStruct> serde::ser::SerializeStruct for CountFields<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), S::Error> {
+        FIELDS_SERIALIZED.with(|count| count.set(count.get() + 1));
+        self.0.serialize_field(key, value)
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        self.0.end()
+    }
+}
+
+#[derive(Serialize)]
+#[serde(with_serializer = "count_fields")]
+struct WithSerializer {
+    a: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    b: Option<i32>,
+    c: i32,
+}
+
+#[test]
+fn test_with_serializer() {
+    FIELDS_SERIALIZED.with(|count| count.set(0));
+    assert_ser_tokens(
+        &WithSerializer { a: 1, b: None, c: 3 },
+        &[
+            Token::Struct { name: "WithSerializer", len: 2 },
+
+            Token::Str("a"),
+            Token::I32(1),
+
+            Token::Str("c"),
+            Token::I32(3),
+
+            Token::StructEnd,
+        ],
+    );
+    assert_eq!(FIELDS_SERIALIZED.with(|count| count.get()), 2);
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct OptionRefStruct<'a> {
+    a: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    b: Option<&'a str>,
+}
+
+#[test]
+fn test_option_ref_field() {
+    assert_ser_tokens(
+        &OptionRefStruct { a: Some("a"), b: Some("b") },
+        &[
+            Token::Struct { name: "OptionRefStruct", len: 2 },
+
+            Token::Str("a"),
+            Token::Some,
+            Token::Str("a"),
+
+            Token::Str("b"),
+            Token::Some,
+            Token::Str("b"),
+
+            Token::StructEnd,
+        ],
+    );
+
+    assert_ser_tokens(
+        &OptionRefStruct { a: None, b: None },
+        &[
+            Token::Struct { name: "OptionRefStruct", len: 1 },
+
+            Token::Str("a"),
+            Token::None,
+
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct NullSentinelStruct {
+    #[serde(null_sentinel = "-1")]
+    id: Option<i32>,
+}
+
+#[test]
+fn test_null_sentinel() {
+    assert_ser_tokens(
+        &NullSentinelStruct { id: Some(5) },
+        &[
+            Token::Struct { name: "NullSentinelStruct", len: 1 },
+
+            Token::Str("id"),
+            Token::I32(5),
+
+            Token::StructEnd,
+        ],
+    );
+
+    assert_ser_tokens(
+        &NullSentinelStruct { id: None },
+        &[
+            Token::Struct { name: "NullSentinelStruct", len: 1 },
+
+            Token::Str("id"),
+            Token::I32(-1),
+
+            Token::StructEnd,
+        ],
+    );
+}
+
+fn rot13(key: &str) -> ::std::borrow::Cow<str> {
+    key.chars()
+        .map(
+            |c| match c {
+                'a'...'m' | 'A'...'M' => (c as u8 + 13) as char,
+                'n'...'z' | 'N'...'Z' => (c as u8 - 13) as char,
+                _ => c,
+            },
+        )
+        .collect::<String>()
+        .into()
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(key_transform = "rot13")]
+struct KeyTransformStruct {
+    hello: i32,
+    #[serde(rename = "World")]
+    world: i32,
+}
+
+#[test]
+fn test_key_transform() {
+    assert_ser_tokens(
+        &KeyTransformStruct { hello: 1, world: 2 },
+        &[
+            Token::Map { len: None },
+
+            Token::Str("uryyb"),
+            Token::I32(1),
+
+            Token::Str("Jbeyq"),
+            Token::I32(2),
+
+            Token::MapEnd,
+        ],
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "type", tag_value = "Widget")]
+struct TaggedStruct {
+    id: u8,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "kind", rename = "gadget")]
+struct TaggedStructDefaultValue {
+    id: u8,
+}
+
+#[test]
+fn test_struct_tag() {
+    assert_ser_tokens(
+        &TaggedStruct { id: 1 },
+        &[
+            Token::Struct { name: "TaggedStruct", len: 2 },
+
+            Token::Str("type"),
+            Token::Str("Widget"),
+
+            Token::Str("id"),
+            Token::U8(1),
+
+            Token::StructEnd,
+        ],
+    );
+
+    assert_ser_tokens(
+        &TaggedStructDefaultValue { id: 2 },
+        &[
+            Token::Struct { name: "gadget", len: 2 },
+
+            Token::Str("kind"),
+            Token::Str("gadget"),
+
+            Token::Str("id"),
+            Token::U8(2),
+
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct CharAsStrStruct {
+    #[serde(char_as_str)]
+    a: char,
+    b: char,
+}
+
+#[test]
+fn test_char_as_str() {
+    assert_tokens(
+        &CharAsStrStruct { a: 'é', b: 'x' },
+        &[
+            Token::Struct { name: "CharAsStrStruct", len: 2 },
+
+            Token::Str("a"),
+            Token::Str("é"),
+
+            Token::Str("b"),
+            Token::Char('x'),
+
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(as_empty_map)]
+struct UnitAsEmptyMap;
+
+#[test]
+fn test_as_empty_map() {
+    assert_ser_tokens(
+        &UnitAsEmptyMap,
+        &[
+            Token::Map { len: Some(0) },
+            Token::MapEnd,
+        ],
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(newtype_option = "inline")]
+struct InlineOption(Option<u8>);
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(newtype_option = "wrap")]
+struct WrapOption(Option<u8>);
+
+#[test]
+fn test_newtype_option() {
+    assert_ser_tokens(&InlineOption(Some(1)), &[Token::Some, Token::U8(1)]);
+    assert_ser_tokens(&InlineOption(None), &[Token::None]);
+
+    assert_ser_tokens(
+        &WrapOption(Some(1)),
+        &[
+            Token::NewtypeStruct { name: "WrapOption" },
+            Token::Some,
+            Token::U8(1),
+        ],
+    );
+    assert_ser_tokens(
+        &WrapOption(None),
+        &[Token::NewtypeStruct { name: "WrapOption" }, Token::None],
+    );
+}
+
+#[derive(Serialize)]
+struct RawStruct {
+    id: u8,
+    #[serde(raw)]
+    cached: String,
+}
+
+#[test]
+fn test_raw() {
+    assert_ser_tokens(
+        &RawStruct { id: 1, cached: "{\"a\":1}".to_owned() },
+        &[
+            Token::Struct { name: "RawStruct", len: 2 },
+
+            Token::Str("id"),
+            Token::U8(1),
+
+            Token::Str("cached"),
+            Token::NewtypeStruct { name: "$serde::private::RawValue" },
+            Token::Str("{\"a\":1}"),
+
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(skip_if_all_fields_skipped)]
+struct MaybeEmpty {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    a: Option<u8>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    b: Vec<u8>,
+    #[serde(skip_serializing)]
+    c: u8,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct ContainsMaybeEmpty {
+    id: u8,
+    #[serde(skip_serializing_if = "MaybeEmpty::__serde_is_empty")]
+    inner: MaybeEmpty,
+}
+
+#[test]
+fn test_skip_if_all_fields_skipped() {
+    assert_ser_tokens(
+        &ContainsMaybeEmpty {
+             id: 1,
+             inner: MaybeEmpty { a: None, b: vec![], c: 2 },
+         },
+        &[
+            Token::Struct { name: "ContainsMaybeEmpty", len: 1 },
+
+            Token::Str("id"),
+            Token::U8(1),
+
+            Token::StructEnd,
+        ],
+    );
+
+    assert_ser_tokens(
+        &ContainsMaybeEmpty {
+             id: 1,
+             inner: MaybeEmpty { a: Some(3), b: vec![], c: 2 },
+         },
+        &[
+            Token::Struct { name: "ContainsMaybeEmpty", len: 2 },
+
+            Token::Str("id"),
+            Token::U8(1),
+
+            Token::Str("inner"),
+            Token::Struct { name: "MaybeEmpty", len: 1 },
+            Token::Str("a"),
+            Token::Some,
+            Token::U8(3),
+            Token::StructEnd,
+
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(non_finite = "null")]
+struct NonFiniteNull {
+    a: f64,
+    b: f32,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(non_finite = "error")]
+struct NonFiniteError {
+    a: f64,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(non_finite = "string")]
+struct NonFiniteString {
+    a: f64,
+    b: f64,
+    c: f32,
+}
+
+#[test]
+fn test_non_finite() {
+    assert_ser_tokens(
+        &NonFiniteNull { a: ::std::f64::NAN, b: 1.5 },
+        &[
+            Token::Struct { name: "NonFiniteNull", len: 2 },
+
+            Token::Str("a"),
+            Token::None,
+
+            Token::Str("b"),
+            Token::F32(1.5),
+
+            Token::StructEnd,
+        ],
+    );
+
+    assert_ser_tokens_error(
+        &NonFiniteError { a: ::std::f64::NAN },
+        &[Token::Struct { name: "NonFiniteError", len: 1 }, Token::Str("a")],
+        "cannot serialize a non-finite float",
+    );
+
+    assert_ser_tokens(
+        &NonFiniteString {
+             a: ::std::f64::NAN,
+             b: ::std::f64::NEG_INFINITY,
+             c: ::std::f32::INFINITY,
+         },
+        &[
+            Token::Struct { name: "NonFiniteString", len: 3 },
+
+            Token::Str("a"),
+            Token::Str("NaN"),
+
+            Token::Str("b"),
+            Token::Str("-Infinity"),
+
+            Token::Str("c"),
+            Token::Str("Infinity"),
+
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct MixedSkippable {
+    a: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    b: Option<u8>,
+    c: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    d: Option<u8>,
+}
+
+#[test]
+fn test_len_with_skippable_fields() {
+    assert_ser_tokens(
+        &MixedSkippable { a: 1, b: None, c: 2, d: Some(3) },
+        &[
+            Token::Struct { name: "MixedSkippable", len: 3 },
+
+            Token::Str("a"),
+            Token::U8(1),
+
+            Token::Str("c"),
+            Token::U8(2),
+
+            Token::Str("d"),
+            Token::Some,
+            Token::U8(3),
+
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", rename_all_skip = "user_id, home_url")]
+struct RenameAllSkip {
+    user_id: u8,
+    home_url: u8,
+    display_name: u8,
+}
+
+#[test]
+fn test_rename_all_skip() {
+    assert_tokens(
+        &RenameAllSkip { user_id: 1, home_url: 2, display_name: 3 },
+        &[
+            Token::Struct { name: "RenameAllSkip", len: 3 },
+
+            Token::Str("user_id"),
+            Token::U8(1),
+
+            Token::Str("home_url"),
+            Token::U8(2),
+
+            Token::Str("displayName"),
+            Token::U8(3),
+
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(unit_as_null)]
+struct UnitAsNull;
+
+#[derive(Debug, PartialEq, Serialize)]
+enum UnitVariantAsNull {
+    #[serde(unit_as_null)]
+    Nothing,
+    Unit,
+}
+
+#[test]
+fn test_unit_as_null() {
+    assert_ser_tokens(&UnitAsNull, &[Token::None]);
+
+    assert_ser_tokens(&UnitVariantAsNull::Nothing, &[Token::None]);
+    assert_ser_tokens(
+        &UnitVariantAsNull::Unit,
+        &[Token::UnitVariant { name: "UnitVariantAsNull", variant: "Unit" }],
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(single_variant = "tagged")]
+enum SingleVariantTagged {
+    Only(u32),
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(single_variant = "transparent")]
+enum SingleVariantTransparent {
+    Only(u32),
+}
+
+#[test]
+fn test_single_variant() {
+    assert_tokens(
+        &SingleVariantTagged::Only(1),
+        &[
+            Token::NewtypeVariant { name: "SingleVariantTagged", variant: "Only" },
+            Token::U32(1),
+        ],
+    );
+
+    assert_tokens(&SingleVariantTransparent::Only(1), &[Token::U32(1)]);
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct SerializeBytes<'a> {
+    #[serde(serialize_bytes)]
+    owned: Vec<u8>,
+    #[serde(serialize_bytes)]
+    borrowed: &'a [u8],
+    seq: Vec<u8>,
+}
+
+#[test]
+fn test_serialize_bytes() {
+    assert_ser_tokens(
+        &SerializeBytes {
+            owned: vec![1, 2],
+            borrowed: b"ab",
+            seq: vec![3],
+        },
+        &[
+            Token::Struct { name: "SerializeBytes", len: 3 },
+
+            Token::Str("owned"),
+            Token::Bytes(&[1, 2]),
+
+            Token::Str("borrowed"),
+            Token::Bytes(b"ab"),
+
+            Token::Str("seq"),
+            Token::Seq { len: Some(1) },
+            Token::U8(3),
+            Token::SeqEnd,
+
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(newtype_name = "none")]
+struct NewtypeNameNone(u8);
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(newtype_name = "Id")]
+struct NewtypeNameOverride(u8);
+
+#[test]
+fn test_newtype_name() {
+    assert_ser_tokens(
+        &NewtypeNameNone(1),
+        &[Token::NewtypeStruct { name: "" }, Token::U8(1)],
+    );
+
+    assert_ser_tokens(
+        &NewtypeNameOverride(1),
+        &[Token::NewtypeStruct { name: "Id" }, Token::U8(1)],
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(field_order = "canonical_length")]
+struct CanonicalLength {
+    ccc: u8,
+    #[serde(rename = "ab")]
+    z: u8,
+    bb: u8,
+    a: u8,
+    #[serde(skip_serializing)]
+    skipped: u8,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(field_order = "canonical_length")]
+enum CanonicalLengthEnum {
+    Struct { bb: u8, a: u8 },
+}
+
+#[test]
+fn test_field_order_canonical_length() {
+    assert_ser_tokens(
+        &CanonicalLength {
+            ccc: 1,
+            z: 2,
+            bb: 3,
+            a: 4,
+            skipped: 5,
+        },
+        &[
+            Token::Struct { name: "CanonicalLength", len: 4 },
+
+            Token::Str("a"),
+            Token::U8(4),
+
+            Token::Str("ab"),
+            Token::U8(2),
+
+            Token::Str("bb"),
+            Token::U8(3),
+
+            Token::Str("ccc"),
+            Token::U8(1),
+
+            Token::StructEnd,
+        ],
+    );
+
+    assert_ser_tokens(
+        &CanonicalLengthEnum::Struct { bb: 1, a: 2 },
+        &[
+            Token::StructVariant { name: "CanonicalLengthEnum", variant: "Struct", len: 2 },
+
+            Token::Str("a"),
+            Token::U8(2),
+
+            Token::Str("bb"),
+            Token::U8(1),
+
+            Token::StructVariantEnd,
+        ],
+    );
+}
+
+#[derive(Serialize)]
+#[serde(partial, field_order = "canonical_length")]
+struct PartialCanonicalLength {
+    long_name: i32,
+    b: i32,
+}
+
+struct SerializeCanonicalFields<'a>(&'a PartialCanonicalLength, &'a [&'a str]);
+
+impl<'a> Serialize for SerializeCanonicalFields<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize_fields(serializer, self.1)
+    }
+}
+
+#[test]
+fn test_partial_field_order_canonical_length() {
+    let value = PartialCanonicalLength { long_name: 1, b: 2 };
+
+    assert_ser_tokens(
+        &SerializeCanonicalFields(&value, &["b"]),
+        &[
+            Token::Struct { name: "PartialCanonicalLength", len: 1 },
+
+            Token::Str("b"),
+            Token::I32(2),
+
+            Token::StructEnd,
+        ],
+    );
+
+    assert_ser_tokens(
+        &SerializeCanonicalFields(&value, &["long_name", "b"]),
+        &[
+            Token::Struct { name: "PartialCanonicalLength", len: 2 },
+
+            Token::Str("b"),
+            Token::I32(2),
+
+            Token::Str("long_name"),
+            Token::I32(1),
+
+            Token::StructEnd,
+        ],
+    );
+}
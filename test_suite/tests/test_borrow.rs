@@ -10,10 +10,10 @@
 extern crate serde_derive;
 
 extern crate serde;
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 extern crate serde_test;
-use serde_test::{Token, assert_de_tokens, assert_de_tokens_error};
+use serde_test::{Token, assert_de_tokens, assert_de_tokens_error, assert_ser_tokens};
 
 use std::borrow::Cow;
 
@@ -172,3 +172,54 @@ fn test_lifetimes() {
         Deserialize::deserialize(deserializer).unwrap()
     }
 }
+
+mod borrowed_str {
+    use super::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<'a, S>(value: &&'a str, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        value.serialize(serializer)
+    }
+
+    pub fn deserialize<'de: 'a, 'a, D>(deserializer: D) -> Result<&'a str, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        <&str>::deserialize(deserializer)
+    }
+}
+
+#[test]
+fn test_with_lifetime() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Borrowing<'a> {
+        #[serde(borrow, with = "borrowed_str")]
+        s: &'a str,
+    }
+
+    assert_ser_tokens(
+        &Borrowing { s: "str" },
+        &[
+            Token::Struct { name: "Borrowing", len: 1 },
+
+            Token::Str("s"),
+            Token::Str("str"),
+
+            Token::StructEnd,
+        ],
+    );
+
+    assert_de_tokens(
+        &Borrowing { s: "str" },
+        &[
+            Token::Struct { name: "Borrowing", len: 1 },
+
+            Token::BorrowedStr("s"),
+            Token::BorrowedStr("str"),
+
+            Token::StructEnd,
+        ],
+    );
+}
@@ -76,6 +76,24 @@ impl Default for StructDefault<String> {
     }
 }
 
+#[derive(PartialEq, Debug, Deserialize)]
+#[serde(default)]
+struct StructDefaultFieldOverride {
+    a: i32,
+    #[serde(default = "field_default")]
+    b: i32,
+}
+
+impl Default for StructDefaultFieldOverride {
+    fn default() -> Self {
+        StructDefaultFieldOverride { a: 100, b: 100 }
+    }
+}
+
+fn field_default() -> i32 {
+    7
+}
+
 #[derive(PartialEq, Debug, Deserialize)]
 struct StructSkipAll {
     #[serde(skip_deserializing)]
@@ -605,6 +623,12 @@ declare_tests! {
             Token::StructEnd,
         ],
     }
+    test_struct_default_field_override {
+        StructDefaultFieldOverride { a: 100, b: 7 } => &[
+            Token::Struct { name: "StructDefaultFieldOverride", len: 0 },
+            Token::StructEnd,
+        ],
+    }
     test_enum_unit {
         Enum::Unit => &[
             Token::UnitVariant { name: "Enum", variant: "Unit" },
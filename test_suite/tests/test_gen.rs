@@ -24,6 +24,8 @@ use self::serde::ser::{Serialize, Serializer};
 use self::serde::de::{DeserializeOwned, Deserializer};
 
 use std::borrow::Cow;
+use std::collections::HashMap;
+use std::hash::Hash;
 use std::marker::PhantomData;
 use std::result::Result as StdResult;
 
@@ -117,6 +119,23 @@ fn test_gen() {
     }
     assert_ser::<MultipleRef<i32>>();
 
+    #[derive(Serialize)]
+    struct IndependentLifetimes<'a, 'b> {
+        a: &'a str,
+        b: &'b str,
+        #[serde(serialize_with="ser_x")]
+        x: &'a X,
+    }
+    assert_ser::<IndependentLifetimes>();
+
+    #[derive(Serialize)]
+    #[serde(tag = "t", content = "c")]
+    enum AdjacentlyTaggedLifetimes<'a, 'b> {
+        Struct { a: &'a str, b: &'b str },
+        Tuple(&'a str, &'b str),
+    }
+    assert_ser::<AdjacentlyTaggedLifetimes>();
+
     #[derive(Serialize, Deserialize)]
     struct Newtype(
         #[serde(serialize_with="ser_x", deserialize_with="de_x")]
@@ -291,6 +310,220 @@ fn test_gen() {
         Variant,
     }
 
+    macro_rules! vec_of {
+        ($t:ty) => { Vec<$t> };
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct MacroFieldType<T> {
+        t: vec_of!(T),
+    }
+    assert::<MacroFieldType<i32>>();
+
+    #[derive(Serialize, Deserialize)]
+    struct UninhabitedField {
+        a: u8,
+        void: EmptyEnum,
+    }
+    assert::<UninhabitedField>();
+
+    #[derive(Serialize, Deserialize)]
+    struct UninhabitedTuple(u8, EmptyEnum);
+    assert::<UninhabitedTuple>();
+
+    #[derive(Serialize, Deserialize)]
+    enum UninhabitedVariant {
+        Unit,
+        Newtype(EmptyEnum),
+        Struct { void: EmptyEnum },
+    }
+    assert::<UninhabitedVariant>();
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(tag = "t")]
+    enum InternallyTaggedSkipAll {
+        Struct {
+            #[serde(skip)]
+            f: u8,
+        },
+    }
+    assert::<InternallyTaggedSkipAll>();
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(tag = "t", content = "c")]
+    enum AdjacentlyTaggedSkipAll {
+        Struct {
+            #[serde(skip)]
+            f: u8,
+        },
+        Tuple(#[serde(skip)] u8),
+    }
+    assert::<AdjacentlyTaggedSkipAll>();
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(untagged)]
+    enum UntaggedSkipAll {
+        Struct {
+            #[serde(skip)]
+            f: u8,
+        },
+    }
+    assert::<UntaggedSkipAll>();
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(transparent)]
+    enum TransparentStructVariant<T> {
+        Struct { t: T },
+    }
+    assert::<TransparentStructVariant<i32>>();
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(transparent)]
+    struct TransparentWith(
+        #[serde(serialize_with="ser_x", deserialize_with="de_x")]
+        X
+    );
+    assert::<TransparentWith>();
+
+    #[derive(Serialize, Deserialize)]
+    #[serde()]
+    struct EmptyAttributes {
+        #[serde()]
+        t: u8,
+    }
+    assert::<EmptyAttributes>();
+
+    #[derive(Serialize, Deserialize)]
+    #[serde()]
+    enum EmptyVariantAttributes {
+        #[serde()]
+        Unit,
+    }
+    assert::<EmptyVariantAttributes>();
+
+    #[derive(Serialize, Deserialize)]
+    enum DeeplyNestedGenerics<K, V>
+    where
+        K: Eq + Hash,
+    {
+        Struct { map: HashMap<K, Vec<Option<V>>> },
+        Tuple(HashMap<K, Vec<Option<V>>>, u8),
+    }
+    assert::<DeeplyNestedGenerics<String, u8>>();
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(tag = "t", content = "c")]
+    enum AdjacentlyTaggedDeeplyNestedGenerics<K, V>
+    where
+        K: Eq + Hash,
+    {
+        Struct { map: HashMap<K, Vec<Option<V>>> },
+        Tuple(HashMap<K, Vec<Option<V>>>, u8),
+    }
+    assert::<AdjacentlyTaggedDeeplyNestedGenerics<String, u8>>();
+
+    #[derive(Serialize)]
+    struct HigherRankedBound<F>
+    where
+        F: for<'a> Fn(&'a str) -> bool,
+    {
+        #[serde(skip_serializing)]
+        filter: F,
+        name: String,
+    }
+    assert_ser::<HigherRankedBound<fn(&str) -> bool>>();
+
+    #[derive(Serialize, Deserialize)]
+    struct ReservedFieldNames {
+        serializer: u8,
+        value: u8,
+        #[serde(skip_serializing_if = "is_zero")]
+        state: u8,
+    }
+    assert::<ReservedFieldNames>();
+
+    #[derive(Serialize, Deserialize)]
+    enum ReservedVariantFieldNames {
+        Struct {
+            serializer: u8,
+            value: u8,
+            #[serde(skip_serializing_if = "is_zero")]
+            state: u8,
+        },
+        Internal {
+            __serializer: u8,
+            __serde_state: u8,
+        },
+    }
+    assert::<ReservedVariantFieldNames>();
+
+    trait Opaque {
+        type Value;
+    }
+    #[derive(Serialize)]
+    enum OpaqueAssociatedType<T: Opaque>
+    where
+        T::Value: SerializeWith,
+    {
+        Struct {
+            #[serde(serialize_with = "SerializeWith::serialize_with")]
+            value: T::Value,
+            #[serde(serialize_with = "SerializeWith::serialize_with")]
+            qualified: <T as Opaque>::Value,
+        },
+        Tuple(
+            #[serde(serialize_with = "SerializeWith::serialize_with")]
+            T::Value,
+        ),
+    }
+    struct OpaqueX;
+    impl Opaque for OpaqueX {
+        type Value = X;
+    }
+    assert_ser::<OpaqueAssociatedType<OpaqueX>>();
+
+    #[derive(Serialize)]
+    struct ErasedSerializeTraitObject {
+        boxed: Box<erased::Serialize>,
+        borrowed: &'static erased::Serialize,
+    }
+    assert_ser::<ErasedSerializeTraitObject>();
+
+    macro_rules! wide_struct {
+        ($($field:ident)*) => {
+            #[derive(Serialize)]
+            struct WideSkippable {
+                $(
+                    #[serde(skip_serializing_if = "is_zero")]
+                    $field: u8,
+                )*
+            }
+        }
+    }
+    wide_struct! {
+        f000 f001 f002 f003 f004 f005 f006 f007 f008 f009
+        f010 f011 f012 f013 f014 f015 f016 f017 f018 f019
+        f020 f021 f022 f023 f024 f025 f026 f027 f028 f029
+        f030 f031 f032 f033 f034 f035 f036 f037 f038 f039
+        f040 f041 f042 f043 f044 f045 f046 f047 f048 f049
+        f050 f051 f052 f053 f054 f055 f056 f057 f058 f059
+        f060 f061 f062 f063 f064 f065 f066 f067 f068 f069
+        f070 f071 f072 f073 f074 f075 f076 f077 f078 f079
+        f080 f081 f082 f083 f084 f085 f086 f087 f088 f089
+        f090 f091 f092 f093 f094 f095 f096 f097 f098 f099
+        f100 f101 f102 f103 f104 f105 f106 f107 f108 f109
+        f110 f111 f112 f113 f114 f115 f116 f117 f118 f119
+        f120 f121 f122 f123 f124 f125 f126 f127 f128 f129
+        f130 f131 f132 f133 f134 f135 f136 f137 f138 f139
+        f140 f141 f142 f143 f144 f145 f146 f147 f148 f149
+        f150 f151 f152 f153 f154 f155 f156 f157 f158 f159
+        f160 f161 f162 f163 f164 f165 f166 f167 f168 f169
+        f170 f171 f172 f173 f174 f175 f176 f177 f178 f179
+        f180 f181 f182 f183 f184 f185 f186 f187 f188 f189
+        f190 f191 f192 f193 f194 f195 f196 f197 f198 f199
+    }
+    assert_ser::<WideSkippable>();
+
     #[cfg(feature = "unstable")]
     #[derive(Serialize, Deserialize)]
     enum EmptyVariants {
@@ -358,6 +591,33 @@ fn test_gen() {
         #[serde(borrow, with = "StrDef")]
         s: Str<'a>,
     }
+
+    mod traits_in_scope {
+        // Generated code must neither depend on nor collide with the traits
+        // that happen to be imported next to the derive.
+        #[allow(unused_imports)]
+        use serde::ser::{SerializeSeq, SerializeStruct, SerializeStructVariant};
+        #[allow(unused_imports)]
+        use serde::de::{MapAccess, SeqAccess, Visitor};
+
+        trait Collide {
+            fn serialize_field(&self) {}
+            fn end(&self) {}
+        }
+
+        impl<T> Collide for T {}
+
+        #[derive(Serialize, Deserialize)]
+        struct First {
+            a: u8,
+        }
+
+        #[derive(Serialize, Deserialize)]
+        enum Second {
+            Tuple(u8, u8),
+            Struct { b: u8 },
+        }
+    }
 }
 
 //////////////////////////////////////////////////////////////////////////
@@ -388,6 +648,23 @@ mod both_x {
     pub use super::{ser_x as serialize, de_x as deserialize};
 }
 
+pub fn is_zero(n: &u8) -> bool {
+    *n == 0
+}
+
+// An object safe trait named `Serialize`, like the one in erased-serde.
+mod erased {
+    pub trait Serialize {
+        fn erased_serialize(&self);
+    }
+}
+
+impl<'a> Serialize for erased::Serialize + 'a {
+    fn serialize<S: Serializer>(&self, _: S) -> StdResult<S::Ok, S::Error> {
+        unimplemented!()
+    }
+}
+
 impl SerializeWith for X {
     fn serialize_with<S: Serializer>(_: &Self, _: S) -> StdResult<S::Ok, S::Error> {
         unimplemented!()
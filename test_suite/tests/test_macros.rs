@@ -11,7 +11,7 @@ extern crate serde_derive;
 
 extern crate serde_test;
 use self::serde_test::{Token, assert_tokens, assert_ser_tokens, assert_de_tokens,
-                       assert_de_tokens_error};
+                       assert_de_tokens_error, assert_ser_tokens_error};
 
 use std::collections::BTreeMap;
 use std::marker::PhantomData;
@@ -415,6 +415,29 @@ fn test_generic_tuple_struct() {
     );
 }
 
+#[test]
+fn test_generic_type_name() {
+    #[derive(Serialize)]
+    struct DefaultedParams<'a, T: 'a = u8>
+    where
+        T: ::std::fmt::Debug,
+    {
+        x: &'a T,
+    }
+
+    assert_ser_tokens(
+        &DefaultedParams { x: &5u8 },
+        &[
+            Token::Struct { name: "DefaultedParams", len: 1 },
+
+            Token::Str("x"),
+            Token::U8(5),
+
+            Token::StructEnd,
+        ],
+    );
+}
+
 #[test]
 fn test_generic_enum_unit() {
     assert_tokens(
@@ -695,6 +718,137 @@ fn test_internally_tagged_enum() {
     );
 }
 
+#[test]
+fn test_internally_tagged_newtype_variant_containing_non_map() {
+    #[derive(Debug, PartialEq, Serialize)]
+    struct Wrapper(u8);
+
+    #[derive(Debug, PartialEq, Serialize)]
+    #[serde(tag = "type")]
+    enum InternallyTagged {
+        Integer(u8),
+        Newtype(Wrapper),
+        Seq(Vec<u8>),
+    }
+
+    assert_ser_tokens_error(
+        &InternallyTagged::Integer(0),
+        &[],
+        "cannot serialize tagged newtype variant InternallyTagged::Integer containing an integer",
+    );
+
+    assert_ser_tokens_error(
+        &InternallyTagged::Newtype(Wrapper(0)),
+        &[],
+        "cannot serialize tagged newtype variant InternallyTagged::Newtype containing an integer",
+    );
+
+    assert_ser_tokens_error(
+        &InternallyTagged::Seq(vec![0]),
+        &[],
+        "cannot serialize tagged newtype variant InternallyTagged::Seq containing a sequence",
+    );
+}
+
+#[test]
+fn test_internally_tagged_flatten_newtype_variant() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(tag = "type")]
+    enum Shape {
+        #[serde(flatten)]
+        Point(Point),
+    }
+
+    assert_ser_tokens(
+        &Shape::Point(Point { x: 1, y: 2 }),
+        &[
+            Token::Map { len: None },
+
+            Token::Str("type"),
+            Token::Str("Point"),
+
+            Token::Str("x"),
+            Token::I32(1),
+
+            Token::Str("y"),
+            Token::I32(2),
+
+            Token::MapEnd,
+        ],
+    );
+
+    assert_de_tokens(
+        &Shape::Point(Point { x: 1, y: 2 }),
+        &[
+            Token::Map { len: None },
+
+            Token::Str("type"),
+            Token::Str("Point"),
+
+            Token::Str("x"),
+            Token::I32(1),
+
+            Token::Str("y"),
+            Token::I32(2),
+
+            Token::MapEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_internally_tagged_variant_in_externally_tagged_enum() {
+    #[derive(Debug, PartialEq, Serialize)]
+    enum Message {
+        #[serde(tag = "kind")]
+        Request { id: u8 },
+        Response(u8),
+        #[serde(tag = "kind")]
+        Ping,
+    }
+
+    assert_ser_tokens(
+        &Message::Request { id: 1 },
+        &[
+            Token::Struct { name: "Message", len: 2 },
+
+            Token::Str("kind"),
+            Token::Str("Request"),
+
+            Token::Str("id"),
+            Token::U8(1),
+
+            Token::StructEnd,
+        ],
+    );
+
+    assert_ser_tokens(
+        &Message::Response(2),
+        &[
+            Token::NewtypeVariant { name: "Message", variant: "Response" },
+            Token::U8(2),
+        ],
+    );
+
+    assert_ser_tokens(
+        &Message::Ping,
+        &[
+            Token::Struct { name: "Message", len: 1 },
+
+            Token::Str("kind"),
+            Token::Str("Ping"),
+
+            Token::StructEnd,
+        ],
+    );
+}
+
 #[test]
 fn test_internally_tagged_struct_variant_containing_unit_variant() {
     #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -1219,3 +1373,369 @@ fn test_rename_all() {
         ],
     );
 }
+
+#[test]
+fn test_rename_all_internally_tagged() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    #[serde(tag = "type", rename_all = "kebab-case")]
+    enum E {
+        UnitVariant,
+        StructVariant { field_name: u8 },
+    }
+
+    assert_tokens(
+        &E::UnitVariant,
+        &[
+            Token::Struct { name: "E", len: 1 },
+            Token::Str("type"),
+            Token::Str("unit-variant"),
+            Token::StructEnd,
+        ],
+    );
+
+    assert_tokens(
+        &E::StructVariant { field_name: 1 },
+        &[
+            Token::Struct { name: "E", len: 2 },
+            Token::Str("type"),
+            Token::Str("struct-variant"),
+            Token::Str("field_name"),
+            Token::U8(1),
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_rename_all_explicit_rename() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    struct S {
+        first_name: u8,
+        #[serde(rename = "last_name")]
+        last_name: u8,
+        #[serde(rename(serialize = "AGE"))]
+        age_years: u8,
+    }
+
+    assert_ser_tokens(
+        &S { first_name: 1, last_name: 2, age_years: 3 },
+        &[
+            Token::Struct { name: "S", len: 3 },
+            Token::Str("firstName"),
+            Token::U8(1),
+            Token::Str("last_name"),
+            Token::U8(2),
+            Token::Str("AGE"),
+            Token::U8(3),
+            Token::StructEnd,
+        ],
+    );
+
+    assert_de_tokens(
+        &S { first_name: 1, last_name: 2, age_years: 3 },
+        &[
+            Token::Struct { name: "S", len: 3 },
+            Token::Str("firstName"),
+            Token::U8(1),
+            Token::Str("last_name"),
+            Token::U8(2),
+            Token::Str("ageYears"),
+            Token::U8(3),
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_cfg_fields() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct S {
+        a: u8,
+        #[cfg(any())]
+        disabled: u8,
+        #[cfg(not(any()))]
+        enabled: u8,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum E {
+        Struct {
+            #[cfg(any())]
+            disabled: u8,
+            #[cfg(not(any()))]
+            enabled: u8,
+        },
+    }
+
+    assert_tokens(
+        &S { a: 1, enabled: 2 },
+        &[
+            Token::Struct { name: "S", len: 2 },
+            Token::Str("a"),
+            Token::U8(1),
+            Token::Str("enabled"),
+            Token::U8(2),
+            Token::StructEnd,
+        ],
+    );
+
+    assert_tokens(
+        &E::Struct { enabled: 1 },
+        &[
+            Token::StructVariant { name: "E", variant: "Struct", len: 1 },
+            Token::Str("enabled"),
+            Token::U8(1),
+            Token::StructVariantEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_empty_braced_struct() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Empty {}
+
+    assert_tokens(
+        &Empty {},
+        &[
+            Token::Struct { name: "Empty", len: 0 },
+            Token::StructEnd,
+        ],
+    );
+
+    assert_de_tokens(
+        &Empty {},
+        &[
+            Token::Map { len: Some(0) },
+            Token::MapEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_zero_sized_fields() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Marker;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct S<T> {
+        marker: Marker,
+        unit: (),
+        empty: [u8; 0],
+        phantom: PhantomData<T>,
+        value: u8,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum E {
+        Newtype(Marker),
+        Tuple(Marker, ()),
+    }
+
+    assert_tokens(
+        &S::<String> {
+             marker: Marker,
+             unit: (),
+             empty: [],
+             phantom: PhantomData,
+             value: 1,
+         },
+        &[
+            Token::Struct { name: "S", len: 5 },
+            Token::Str("marker"),
+            Token::UnitStruct { name: "Marker" },
+            Token::Str("unit"),
+            Token::Unit,
+            Token::Str("empty"),
+            Token::Tuple { len: 0 },
+            Token::TupleEnd,
+            Token::Str("phantom"),
+            Token::UnitStruct { name: "PhantomData" },
+            Token::Str("value"),
+            Token::U8(1),
+            Token::StructEnd,
+        ],
+    );
+
+    assert_tokens(
+        &E::Newtype(Marker),
+        &[
+            Token::NewtypeVariant { name: "E", variant: "Newtype" },
+            Token::UnitStruct { name: "Marker" },
+        ],
+    );
+
+    assert_tokens(
+        &E::Tuple(Marker, ()),
+        &[
+            Token::TupleVariant { name: "E", variant: "Tuple", len: 2 },
+            Token::UnitStruct { name: "Marker" },
+            Token::Unit,
+            Token::TupleVariantEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_unsized_smart_pointer_fields() {
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    #[derive(Serialize)]
+    struct S {
+        boxed: Box<str>,
+        rc: Rc<str>,
+        arc: Arc<str>,
+    }
+
+    #[derive(Serialize)]
+    enum E {
+        Boxed(Box<str>),
+        Rc(Rc<str>, Arc<str>),
+    }
+
+    assert_ser_tokens(
+        &S {
+             boxed: "a".into(),
+             rc: "b".into(),
+             arc: "c".into(),
+         },
+        &[
+            Token::Struct { name: "S", len: 3 },
+            Token::Str("boxed"),
+            Token::Str("a"),
+            Token::Str("rc"),
+            Token::Str("b"),
+            Token::Str("arc"),
+            Token::Str("c"),
+            Token::StructEnd,
+        ],
+    );
+
+    assert_ser_tokens(
+        &E::Boxed("a".into()),
+        &[
+            Token::NewtypeVariant { name: "E", variant: "Boxed" },
+            Token::Str("a"),
+        ],
+    );
+
+    assert_ser_tokens(
+        &E::Rc("b".into(), "c".into()),
+        &[
+            Token::TupleVariant { name: "E", variant: "Rc", len: 2 },
+            Token::Str("b"),
+            Token::Str("c"),
+            Token::TupleVariantEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_fieldless_enum() {
+    #[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Debug)]
+    #[repr(u8)]
+    enum Fieldless {
+        A,
+        B,
+        #[serde(rename = "c")]
+        C,
+        D,
+    }
+
+    assert_tokens(
+        &Fieldless::A,
+        &[Token::UnitVariant { name: "Fieldless", variant: "A" }],
+    );
+
+    assert_tokens(
+        &Fieldless::C,
+        &[Token::UnitVariant { name: "Fieldless", variant: "c" }],
+    );
+
+    assert_tokens(
+        &Fieldless::D,
+        &[Token::UnitVariant { name: "Fieldless", variant: "D" }],
+    );
+}
+
+#[test]
+fn test_uninhabited_fields() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Void {}
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum E {
+        Unit,
+        Newtype(Void),
+        Struct { void: Void },
+    }
+
+    assert_tokens(&E::Unit, &[Token::UnitVariant { name: "E", variant: "Unit" }]);
+
+    assert_de_tokens_error::<E>(
+        &[
+            Token::NewtypeVariant { name: "E", variant: "Newtype" },
+            Token::UnitVariant { name: "Void", variant: "A" },
+        ],
+        "unknown variant `A`, there are no variants",
+    );
+}
+
+#[test]
+fn test_macro_field_types() {
+    macro_rules! int {
+        () => { i32 };
+    }
+
+    macro_rules! vec_of {
+        ($t:ty) => { Vec<$t> };
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Struct {
+        a: int!(),
+        b: vec_of!(u8),
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Enum {
+        Newtype(int!()),
+        Struct { b: vec_of!(u8) },
+    }
+
+    assert_tokens(
+        &Struct { a: 1, b: vec![2] },
+        &[
+            Token::Struct { name: "Struct", len: 2 },
+            Token::Str("a"),
+            Token::I32(1),
+            Token::Str("b"),
+            Token::Seq { len: Some(1) },
+            Token::U8(2),
+            Token::SeqEnd,
+            Token::StructEnd,
+        ],
+    );
+
+    assert_tokens(
+        &Enum::Newtype(1),
+        &[
+            Token::NewtypeVariant { name: "Enum", variant: "Newtype" },
+            Token::I32(1),
+        ],
+    );
+
+    assert_tokens(
+        &Enum::Struct { b: vec![2] },
+        &[
+            Token::StructVariant { name: "Enum", variant: "Struct", len: 1 },
+            Token::Str("b"),
+            Token::Seq { len: Some(1) },
+            Token::U8(2),
+            Token::SeqEnd,
+            Token::StructVariantEnd,
+        ],
+    );
+}
@@ -14,6 +14,8 @@ use std::net;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 use std::ffi::CString;
+use std::rc::Rc;
+use std::sync::Arc;
 
 #[cfg(unix)]
 use std::str;
@@ -299,6 +301,17 @@ declare_tests! {
     test_box {
         Box::new(0i32) => &[Token::I32(0)],
     }
+    test_boxed_str {
+        String::from("abc").into_boxed_str() => &[Token::Str("abc")],
+    }
+    test_rc {
+        Rc::new(0i32) => &[Token::I32(0)],
+        Rc::<str>::from("abc") => &[Token::Str("abc")],
+    }
+    test_arc {
+        Arc::new(0i32) => &[Token::I32(0)],
+        Arc::<str>::from("abc") => &[Token::Str("abc")],
+    }
     test_boxed_slice {
         Box::new([0, 1, 2]) => &[
             Token::Tuple { len: 3 },
@@ -415,3 +428,66 @@ fn test_enum_skipped() {
         "the enum variant Enum::SkippedMap cannot be serialized",
     );
 }
+
+#[test]
+fn test_internally_tagged_enum_skipped() {
+    #[derive(Serialize)]
+    #[serde(tag = "type")]
+    enum Tagged {
+        Active { id: u8 },
+        #[serde(skip_serializing)]
+        Transient { _id: u8 },
+    }
+
+    assert_ser_tokens(
+        &Tagged::Active { id: 1 },
+        &[
+            Token::Struct { name: "Tagged", len: 2 },
+            Token::Str("type"),
+            Token::Str("Active"),
+            Token::Str("id"),
+            Token::U8(1),
+            Token::StructEnd,
+        ],
+    );
+    assert_ser_tokens_error(
+        &Tagged::Transient { _id: 1 },
+        &[],
+        "the enum variant Tagged::Transient cannot be serialized",
+    );
+}
+
+#[test]
+fn test_recursive_enum() {
+    #[derive(Serialize)]
+    enum Tree {
+        Leaf(u8),
+        Node(Box<Tree>, Box<Tree>),
+    }
+
+    fn build(depth: u8) -> Tree {
+        if depth == 0 {
+            Tree::Leaf(0)
+        } else {
+            Tree::Node(Box::new(build(depth - 1)), Box::new(Tree::Leaf(depth)))
+        }
+    }
+
+    assert_ser_tokens(
+        &build(2),
+        &[
+            Token::TupleVariant { name: "Tree", variant: "Node", len: 2 },
+                Token::TupleVariant { name: "Tree", variant: "Node", len: 2 },
+                    Token::NewtypeVariant { name: "Tree", variant: "Leaf" },
+                    Token::U8(0),
+
+                    Token::NewtypeVariant { name: "Tree", variant: "Leaf" },
+                    Token::U8(1),
+                Token::TupleVariantEnd,
+
+                Token::NewtypeVariant { name: "Tree", variant: "Leaf" },
+                Token::U8(2),
+            Token::TupleVariantEnd,
+        ],
+    );
+}